@@ -0,0 +1,104 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{INVALID_ALIAS_NAME, NOT_FOUND};
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Write};
+
+/**
+ * Define or list aliases.
+ *
+ * `alias name=value` defines an alias, `alias name` prints its definition,
+ * and `alias` or `alias -p` lists every alias, in a form that can be read back by the shell.
+ *
+ * # Arguments
+ * * `aliases` - The aliases of the shell.
+ * * `args` - The arguments passed to the command.
+ * * `out` - The stream the definitions are written to.
+ *
+ * # Example
+ * ```rust
+ * use std::collections::BTreeMap;
+ * use std::io::stdout;
+ * use shell::commands::alias;
+ *
+ * let mut aliases = BTreeMap::new();
+ * alias(&mut aliases, &["ll=ls -l"], &mut stdout());
+ * assert_eq!(aliases.get("ll").map(String::as_str), Some("ls -l"));
+ * ```
+ */
+pub fn alias(aliases: &mut BTreeMap<String, String>, args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
+    if args.is_empty() || args == ["-p"] {
+        for (name, value) in aliases.iter() {
+            writeln!(out, "alias {}='{}'", name, value.replace('\'', "'\\''"))?;
+        }
+        return Ok(());
+    }
+
+    let mut errors = Vec::new();
+    for arg in args {
+        match arg.split_once('=') {
+            Some((name, value)) if is_valid_alias_name(name) => {
+                aliases.insert(name.to_owned(), value.to_owned());
+            }
+            Some((name, _)) => errors.push(format!("alias: `{}': {INVALID_ALIAS_NAME}", name)),
+            None => match aliases.get(*arg) {
+                Some(value) => writeln!(out, "alias {}='{}'", arg, value.replace('\'', "'\\''"))?,
+                None => errors.push(format!("alias: {}: {NOT_FOUND}", arg)),
+            },
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ShellError::IoError(Error::new(ErrorKind::NotFound, errors.join("\n"))));
+    }
+
+    Ok(())
+}
+
+/**
+ * Remove aliases, or every alias with `-a`.
+ *
+ * # Arguments
+ * * `aliases` - The aliases of the shell.
+ * * `args` - The names of the aliases to remove.
+ *
+ * # Example
+ * ```rust
+ * use std::collections::BTreeMap;
+ * use shell::commands::unalias;
+ *
+ * let mut aliases = BTreeMap::from([("ll".to_owned(), "ls -l".to_owned())]);
+ * unalias(&mut aliases, &["ll"]);
+ * assert!(aliases.is_empty());
+ * ```
+ */
+pub fn unalias(aliases: &mut BTreeMap<String, String>, args: &[&str]) -> Result<(), ShellError> {
+    if args.is_empty() {
+        return Err(ShellError::InvalidArguments("unalias: usage: unalias [-a] name [name ...]".to_owned()));
+    }
+    if args == ["-a"] {
+        aliases.clear();
+        return Ok(());
+    }
+
+    let missing: Vec<String> = args
+        .iter()
+        .filter(|name| aliases.remove(**name).is_none())
+        .map(|name| format!("unalias: {}: {NOT_FOUND}", name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(ShellError::IoError(Error::new(ErrorKind::NotFound, missing.join("\n"))));
+    }
+
+    Ok(())
+}
+
+/**
+ * Check whether a name can be used for an alias: it cannot be empty nor hold blanks,
+ * quotes, slashes or characters that start an expansion.
+ */
+fn is_valid_alias_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || matches!(c, '/' | '$' | '`' | '=' | '\'' | '"' | '\\' | '|' | '&' | ';' | '<' | '>' | '(' | ')')
+        })
+}
@@ -1,41 +1,51 @@
 use crate::utils::error::ShellError;
-use crate::utils::messages::{NO_FILE_SPECIFIED, NO_SUCH_FILE};
+use crate::utils::messages::NO_SUCH_FILE;
 use std::fs::File;
-use std::io::Read;
+use std::io::{copy, Error, ErrorKind, Read, Write};
 use std::path::Path;
 
 /**
  * Display the contents of a file.
  *
+ * Without arguments, or for the `-` argument, the input stream is copied instead.
+ *
  * # Arguments
  * * `current_dir` - The current directory.
  * * `args` - The arguments passed to the command.
+ * * `input` - The stream read when no file is given.
+ * * `out` - The stream the contents are written to.
  *
  * # Example
  * ```rust
+ * use std::io::{stdin, stdout};
  * use std::path::Path;
  * use shell::commands::cat;
  *
  * let current_dir = Path::new("/home/user");
  * let args = vec!["file.txt"];
  *
- * cat(current_dir, &args);
+ * cat(current_dir, &args, &mut stdin(), &mut stdout());
  * ```
 */
-pub fn cat(current_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
+pub fn cat(current_dir: &Path, args: &[&str], input: &mut dyn Read, out: &mut dyn Write) -> Result<(), ShellError> {
     if args.is_empty() {
-        return Err(ShellError::InvalidArguments(NO_FILE_SPECIFIED.to_owned()));
+        copy(input, out)?;
+        return Ok(());
     }
 
     for file_name in args {
+        if *file_name == "-" {
+            copy(input, out)?;
+            continue;
+        }
+
         let file_path = current_dir.join(file_name);
-        let mut file = File::open(&file_path).map_err(|_| ShellError::InvalidArguments(format!("cat: {}: {NO_SUCH_FILE}", file_name)))?;
+        let mut file = File::open(&file_path).map_err(|_| ShellError::IoError(Error::new(ErrorKind::NotFound, format!("cat: {}: {NO_SUCH_FILE}", file_name))))?;
         let mut contents = String::new();
 
-        file.read_to_string(&mut contents).map_err(|_| ShellError::InvalidArguments(format!("cat: {}: {NO_SUCH_FILE}", file_name)))?;
-        print!("{}", contents);
+        file.read_to_string(&mut contents).map_err(|_| ShellError::IoError(Error::new(ErrorKind::NotFound, format!("cat: {}: {NO_SUCH_FILE}", file_name))))?;
+        write!(out, "{}", contents)?;
     }
-    // println!();
 
     Ok(())
 }
\ No newline at end of file
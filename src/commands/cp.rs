@@ -86,7 +86,7 @@ pub fn copy_dir_all(src: &Path, dst: &Path) -> Result<(), Error> {
         if ty.is_dir() {
             copy_dir_all(&entry.path(), &dst_path)?;
         } else {
-            copy(&entry.path(), &dst_path)?;
+            copy(entry.path(), &dst_path)?;
         }
     }
 
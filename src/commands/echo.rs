@@ -1,19 +1,21 @@
 use crate::utils::error::ShellError;
-use crate::utils::messages::COMMAND_NOT_FOUND;
+use std::io::Write;
 
 /**
  * Print the given arguments to the console.
  *
  * # Arguments
  * * `args` - The arguments to print.
+ * * `out` - The stream the arguments are written to.
  *
  * # Example
  * ```rust
+ * use std::io::stdout;
  * use shell::commands::echo;
  *
  * let args = vec!["Hello, world!"];
  *
- * echo(&args);
+ * echo(&args, &mut stdout());
  * ```
  *
  * # Output
@@ -21,31 +23,14 @@ use crate::utils::messages::COMMAND_NOT_FOUND;
  * Hello, world!
  * ```
 */
-pub fn echo(args: &[&str]) -> Result<(), ShellError> {
+pub fn echo(args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
     if args.is_empty() {
-        println!();
+        writeln!(out)?;
         return Ok(());
     }
 
-    let mut arguments = Vec::new();
-    for arg in args {
-        if arg.starts_with('"') && arg.ends_with('"') {
-            arguments.push(&arg[1..arg.len() - 1]);
-        } else {
-            arguments.push(arg);
-        }
-    }
-
-    let mut text = arguments.join(" ");
-    if text.starts_with('"') && text.ends_with('"') {
-        text = text[1..text.len() - 1].to_string();
-    } else if text.starts_with('\'') && text.ends_with('\'') {
-        text = text[1..text.len() - 1].to_string();
-    } else if text.starts_with('`') && text.ends_with('`') {
-        return Err(ShellError::InvalidArguments(format!("???: {COMMAND_NOT_FOUND}: {}", text)));
-    }
-
-    println!("{}", escape(text));
+    let text = args.join(" ");
+    writeln!(out, "{}", escape(text))?;
     Ok(())
 }
 
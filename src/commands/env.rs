@@ -0,0 +1,96 @@
+use crate::exec::external::{find_in_path, run_external};
+use crate::exec::stream::Streams;
+use crate::utils::error::ShellError;
+use crate::variables::is_valid_name;
+use std::io::Write;
+use std::path::Path;
+
+/**
+ * Print the environment, or run a program in a modified environment.
+ *
+ * `env` lists the exported variables, `env NAME=value... program args...` runs the program
+ * with the extra variables, and `-i` starts from an empty environment.
+ *
+ * # Arguments
+ * * `current_dir` - The current directory.
+ * * `exported` - The exported variables of the shell.
+ * * `args` - The arguments passed to the command.
+ * * `streams` - The streams of the command.
+ *
+ * # Example
+ * ```rust
+ * use std::path::Path;
+ * use shell::commands::env;
+ * use shell::exec::stream::Streams;
+ *
+ * let exported = vec![("PATH".to_owned(), "/usr/bin:/bin".to_owned())];
+ * let status = env(Path::new("/tmp"), &exported, &["LANG=C", "true"], &mut Streams::inherit());
+ * assert_eq!(status.unwrap(), 0);
+ * ```
+ */
+pub fn env(current_dir: &Path, exported: &[(String, String)], args: &[&str], streams: &mut Streams) -> Result<i32, ShellError> {
+    let mut args = args;
+    let mut vars = exported.to_vec();
+
+    if args.first() == Some(&"-i") {
+        vars.clear();
+        args = &args[1..];
+    }
+
+    while let Some((name, value)) = args.first().and_then(|arg| arg.split_once('=')) {
+        if !is_valid_name(name) {
+            break;
+        }
+        vars.retain(|(key, _)| key != name);
+        vars.push((name.to_owned(), value.to_owned()));
+        args = &args[1..];
+    }
+
+    match args.split_first() {
+        Some((program, program_args)) => {
+            let mut program = program.to_string();
+            if !vars.iter().any(|(key, _)| key == "PATH") {
+                let path = exported.iter().find(|(key, _)| key == "PATH").map(|(_, value)| value.as_str());
+                if let Some(found) = path.and_then(|path| find_in_path(&program, path)) {
+                    program = found.to_string_lossy().to_string();
+                }
+            }
+            run_external(current_dir, &program, program_args, &vars, streams)
+        }
+        None => {
+            for (name, value) in vars {
+                writeln!(streams.stdout, "{}={}", name, value)?;
+            }
+            Ok(0)
+        }
+    }
+}
+
+/**
+ * Print the values of environment variables, or the whole environment without arguments.
+ *
+ * Returns 1 when one of the variables is not exported.
+ *
+ * # Arguments
+ * * `exported` - The exported variables of the shell.
+ * * `args` - The names of the variables.
+ * * `out` - The stream the values are written to.
+ */
+pub fn printenv(exported: &[(String, String)], args: &[&str], out: &mut dyn Write) -> Result<i32, ShellError> {
+    if args.is_empty() {
+        for (name, value) in exported {
+            writeln!(out, "{}={}", name, value)?;
+        }
+        return Ok(0);
+    }
+
+    let mut status = 0;
+    for name in args {
+        match exported.iter().find(|(key, _)| key == name) {
+            Some((_, value)) => writeln!(out, "{}", value)?,
+            None => status = 1,
+        }
+    }
+
+    Ok(status)
+}
@@ -1,10 +1,35 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{MANY_ARGS, NUMERIC_ARGUMENT_REQUIRED};
+use std::io::{self, Write};
+
 /**
  * Exit command
  *
- * This command is used to exit the shell
+ * This command is used to exit the shell, with the given status or the status of the last command.
+ * It never returns `Ok`: the `ShellError::Exit` error unwinds up to the main loop.
+ *
+ * # Arguments
+ * * `last_status` - The status of the last command, used when no status is given.
+ * * `args` - The arguments passed to the command.
+ * * `err` - The stream errors are written to.
  */
-pub fn exit() -> ! {
-    std::process::exit(0);
+pub fn exit(last_status: i32, args: &[&str], err: &mut dyn Write) -> Result<(), ShellError> {
+    if args.len() > 1 {
+        return Err(ShellError::IoError(io::Error::other(format!("exit: {MANY_ARGS}"))));
+    }
+
+    let code = match args.first() {
+        None => last_status,
+        Some(arg) => match arg.parse::<i64>() {
+            Ok(code) => code.rem_euclid(256) as i32,
+            Err(_) => {
+                writeln!(err, "\x1b[31mexit: {}: {NUMERIC_ARGUMENT_REQUIRED}\x1b[0m", arg)?;
+                2
+            }
+        },
+    };
+
+    Err(ShellError::Exit(code))
 }
 
 /**
@@ -12,6 +37,6 @@ pub fn exit() -> ! {
  *
  * This command is used to clear the terminal screen
  */
-pub fn clear() {
-    print!("\x1B[2J\x1B[1;1H");
+pub fn clear(out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "\x1B[2J\x1B[1;1H")
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::NOT_A_VALID_IDENTIFIER;
+use crate::variables::{is_valid_name, Variables};
+use std::io::Write;
+
+/**
+ * Export variables to the environment of spawned programs.
+ *
+ * `export NAME=value` sets and exports a variable, `export NAME` exports an existing one,
+ * and `export` or `export -p` lists the exported variables.
+ *
+ * # Arguments
+ * * `variables` - The variables of the shell.
+ * * `args` - The arguments passed to the command.
+ * * `out` - The stream the list is written to.
+ *
+ * # Example
+ * ```rust
+ * use std::io::stdout;
+ * use shell::commands::export;
+ * use shell::variables::Variables;
+ *
+ * let mut variables = Variables::default();
+ * export(&mut variables, &["EDITOR=vim"], &mut stdout());
+ * assert_eq!(variables.get("EDITOR"), Some("vim"));
+ * ```
+ */
+pub fn export(variables: &mut Variables, args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
+    if args.is_empty() || args == ["-p"] {
+        for (name, value) in variables.exported() {
+            writeln!(out, "export {}=\"{}\"", name, escape_value(&value))?;
+        }
+        return Ok(());
+    }
+
+    let mut invalid = Vec::new();
+    for arg in args {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (*arg, None),
+        };
+
+        if is_valid_name(name) {
+            variables.export(name, value);
+        } else {
+            invalid.push(format!("export: `{}': {NOT_A_VALID_IDENTIFIER}", arg));
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(ShellError::InvalidArguments(invalid.join("\n")));
+    }
+
+    Ok(())
+}
+
+/**
+ * Escape a value so it can be read back inside double quotes.
+ */
+pub fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
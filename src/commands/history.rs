@@ -0,0 +1,70 @@
+use crate::editor::history::History;
+use crate::utils::error::ShellError;
+use crate::utils::messages::{NUMERIC_ARGUMENT_REQUIRED, POSITION_OUT_OF_RANGE};
+use std::io::{Error, ErrorKind, Write};
+
+/**
+ * List or edit the command history.
+ *
+ * `history` lists every entry with its number, `history N` lists the last `N` entries,
+ * `history -c` clears the history of this shell, leaving the history file as it is, and
+ * `history -d N` deletes the entry `N`, counting from the end when negative.
+ *
+ * # Arguments
+ * * `history` - The history of the shell.
+ * * `args` - The arguments passed to the command.
+ * * `out` - The stream the entries are written to.
+ *
+ * # Example
+ * ```rust
+ * use std::io::stdout;
+ * use shell::commands::history;
+ * use shell::editor::history::History;
+ *
+ * let mut entries = History::default();
+ * history(&mut entries, &["-c"], &mut stdout());
+ * assert!(entries.entries().is_empty());
+ * ```
+ */
+pub fn history(history: &mut History, args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
+    match args {
+        [] => list(history, history.entries().len(), out),
+        ["-c"] => {
+            history.clear();
+            Ok(())
+        }
+        ["-d", offset] => {
+            let len = history.entries().len() as i64;
+            let index = match offset.parse::<i64>() {
+                Ok(offset) if offset < 0 => len + offset,
+                Ok(offset) => offset - 1,
+                Err(_) => return Err(out_of_range(offset)),
+            };
+            if index < 0 || !history.delete(index as usize) {
+                return Err(out_of_range(offset));
+            }
+            Ok(())
+        }
+        [count] => match count.parse::<usize>() {
+            Ok(count) => list(history, count, out),
+            Err(_) => Err(ShellError::InvalidArguments(format!("history: {}: {NUMERIC_ARGUMENT_REQUIRED}", count))),
+        },
+        _ => Err(ShellError::InvalidArguments("history: usage: history [-c] [-d offset] [n]".to_owned())),
+    }
+}
+
+/**
+ * Print the last `count` entries, numbered from 1.
+ */
+fn list(history: &History, count: usize, out: &mut dyn Write) -> Result<(), ShellError> {
+    let entries = history.entries();
+    let start = entries.len().saturating_sub(count);
+    for (i, entry) in entries.iter().enumerate().skip(start) {
+        writeln!(out, "{:5}  {}", i + 1, entry)?;
+    }
+    Ok(())
+}
+
+fn out_of_range(offset: &str) -> ShellError {
+    ShellError::IoError(Error::new(ErrorKind::InvalidInput, format!("history: {}: {POSITION_OUT_OF_RANGE}", offset)))
+}
@@ -0,0 +1,36 @@
+use crate::expansion::arithmetic::evaluate;
+use crate::utils::error::ShellError;
+use crate::utils::messages::EXPRESSION_EXPECTED;
+use crate::variables::Variables;
+
+/**
+ * Evaluate arithmetic expressions, usually to assign variables.
+ *
+ * Returns 0 if the last expression is not zero, and 1 otherwise.
+ *
+ * # Arguments
+ * * `variables` - The variables of the shell.
+ * * `args` - The expressions to evaluate.
+ *
+ * # Example
+ * ```rust
+ * use shell::commands::r#let;
+ * use shell::variables::Variables;
+ *
+ * let mut variables = Variables::default();
+ * assert_eq!(r#let(&mut variables, &["i = 2", "i *= 3"]).unwrap(), 0);
+ * assert_eq!(variables.get("i"), Some("6"));
+ * ```
+ */
+pub fn r#let(variables: &mut Variables, args: &[&str]) -> Result<i32, ShellError> {
+    if args.is_empty() {
+        return Err(ShellError::InvalidArguments(format!("let: {EXPRESSION_EXPECTED}")));
+    }
+
+    let mut value = 0;
+    for arg in args {
+        value = evaluate(variables, arg)?;
+    }
+
+    Ok(if value != 0 { 0 } else { 1 })
+}
@@ -0,0 +1,66 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{NOT_A_VALID_IDENTIFIER, NOT_IN_FUNCTION};
+use crate::variables::{is_valid_name, Variable, Variables};
+
+/**
+ * Declare variables local to the function being run.
+ *
+ * `local name=value` sets the variable and `local name` unsets it, until the function returns
+ * and the previous value is put back. Declaring a local variable again without a value keeps
+ * its value. Local variables are not exported.
+ *
+ * # Arguments
+ * * `variables` - The variables of the shell.
+ * * `scope` - The variables already made local in the function with their previous state,
+ *   or `None` outside functions.
+ * * `args` - The arguments passed to the command.
+ *
+ * # Example
+ * ```rust
+ * use shell::commands::local;
+ * use shell::variables::Variables;
+ *
+ * let mut variables = Variables::default();
+ * let mut scope = Vec::new();
+ * local(&mut variables, Some(&mut scope), &["count=1"]);
+ * assert_eq!(variables.get("count"), Some("1"));
+ * assert_eq!(scope, vec![("count".to_owned(), None)]);
+ * ```
+ */
+pub fn local(
+    variables: &mut Variables,
+    scope: Option<&mut Vec<(String, Option<Variable>)>>,
+    args: &[&str],
+) -> Result<(), ShellError> {
+    let Some(scope) = scope else {
+        return Err(ShellError::InvalidArguments(format!("local: {NOT_IN_FUNCTION}")));
+    };
+
+    let mut invalid = Vec::new();
+    for arg in args {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (*arg, None),
+        };
+        if !is_valid_name(name) {
+            invalid.push(format!("local: `{}': {NOT_A_VALID_IDENTIFIER}", arg));
+            continue;
+        }
+
+        // Only the state from before the first declaration is put back, and a name declared
+        // again without a value keeps its local value.
+        if !scope.iter().any(|(local, _)| local == name) {
+            let saved = variables.unset(name);
+            scope.push((name.to_owned(), saved));
+        }
+        if let Some(value) = value {
+            variables.set(name, value);
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(ShellError::InvalidArguments(invalid.join("\n")));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{LOOP_COUNT_OUT_OF_RANGE, MANY_ARGS, NUMERIC_ARGUMENT_REQUIRED, ONLY_IN_LOOP};
+use std::io::{self, Write};
+
+/**
+ * Break command
+ *
+ * This command is used to stop the `n`th enclosing loop, the innermost one by default.
+ * Like `exit`, it never returns `Ok` inside a loop: the `ShellError::Break` error unwinds up to the loop.
+ *
+ * # Arguments
+ * * `loops` - The number of loops being run.
+ * * `args` - The arguments passed to the command.
+ * * `err` - The stream warnings are written to.
+ */
+pub fn r#break(loops: usize, args: &[&str], err: &mut dyn Write) -> Result<(), ShellError> {
+    match loop_count("break", loops, args, err)? {
+        Some(count) => Err(ShellError::Break(count)),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Continue command
+ *
+ * This command is used to go on with the next iteration of the `n`th enclosing loop,
+ * the innermost one by default, skipping the rest of the bodies of the loops inside it.
+ *
+ * # Arguments
+ * * `loops` - The number of loops being run.
+ * * `args` - The arguments passed to the command.
+ * * `err` - The stream warnings are written to.
+ */
+pub fn r#continue(loops: usize, args: &[&str], err: &mut dyn Write) -> Result<(), ShellError> {
+    match loop_count("continue", loops, args, err)? {
+        Some(count) => Err(ShellError::Continue(count)),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Get the number of loops `break` or `continue` applies to, at most the number of loops being run.
+ *
+ * Outside loops, a warning is written and `None` is returned, the command doing nothing as in bash.
+ */
+fn loop_count(name: &str, loops: usize, args: &[&str], err: &mut dyn Write) -> Result<Option<u32>, ShellError> {
+    if args.len() > 1 {
+        return Err(ShellError::IoError(io::Error::other(format!("{}: {MANY_ARGS}", name))));
+    }
+    let count = match args.first() {
+        None => 1,
+        Some(arg) => match arg.parse::<i64>() {
+            Ok(count) if count >= 1 => count,
+            Ok(_) => return Err(ShellError::IoError(io::Error::other(format!("{}: {}: {LOOP_COUNT_OUT_OF_RANGE}", name, arg)))),
+            Err(_) => return Err(ShellError::InvalidArguments(format!("{}: {}: {NUMERIC_ARGUMENT_REQUIRED}", name, arg))),
+        },
+    };
+
+    if loops == 0 {
+        writeln!(err, "\x1b[31m{}: {ONLY_IN_LOOP}\x1b[0m", name)?;
+        return Ok(None);
+    }
+    Ok(Some(count.min(loops as i64) as u32))
+}
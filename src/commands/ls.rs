@@ -4,6 +4,7 @@ use crate::utils::error::ShellError;
 use crate::utils::messages::{CANNOT_ACCESS, INVALID_FLAG, NOTHING, NO_SUCH_FILE};
 use crate::utils::utils::{extrac_guid_from_file, permissions_string};
 use std::fs::{metadata, read_dir, read_link, DirEntry, Metadata};
+use std::io::Write;
 use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 
@@ -21,23 +22,25 @@ struct CustomEntry {
  * # Arguments
  * * `base_dir` - The base directory.
  * * `args` - The arguments passed to the command.
+ * * `out` - The stream the listing is written to.
  *
  * # Example
  * ```rust
+ * use std::io::stdout;
  * use std::path::Path;
  * use shell::commands::ls;
  *
  * let base_dir = Path::new("/home/user");
  * let args = vec!["-a"];
  *
- * ls(base_dir, &args);
+ * ls(base_dir, &args, &mut stdout());
  * ```
  */
-pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
+pub fn ls(base_dir: &Path, args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
     let mut show_hidden = false;
     let mut long_format = false;
     let mut show_indicator = false;
-    let mut target_dir = base_dir;
+    let mut target_dir = base_dir.to_path_buf();
 
     for arg in args {
         match *arg {
@@ -48,7 +51,7 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
                 return Err(ShellError::InvalidArguments(INVALID_FLAG.to_string()));
             }
             _ => {
-                let path = Path::new(arg);
+                let path = base_dir.join(arg);
                 if path.is_dir() {
                     target_dir = path;
                 } else {
@@ -61,7 +64,7 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
         }
     }
 
-    let entries: Vec<DirEntry> = read_dir(target_dir)?
+    let entries: Vec<DirEntry> = read_dir(&target_dir)?
         .filter_map(|entry| entry.ok())
         .collect();
 
@@ -85,7 +88,7 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
             0,
             CustomEntry {
                 name: ".".to_string(),
-                metadata: metadata(target_dir)?,
+                metadata: metadata(&target_dir)?,
             },
         );
     }
@@ -101,7 +104,7 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
     }
 
     if long_format {
-        println!("total {}", block_size / 2);
+        writeln!(out, "total {}", block_size / 2)?;
     }
 
     for entry in all_entries {
@@ -155,7 +158,7 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
             if long_format {
                 name_display = format!("{} -> {}", name, target);
             } else {
-                name_display = format!("{}", name);
+                name_display = name.clone();
             }
         } else if show_indicator {
             if metadata.is_dir() {
@@ -166,17 +169,18 @@ pub fn ls(base_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
         }
 
         if long_format {
-            println!(
+            writeln!(
+                out,
                 "{:<1}{:<9} {:>2} {:<7} {:<7} {} {} {}{}{}",
                 file_type, permissions, nlink, extrac_guid_from_file(uid, "/etc/passwd"), extrac_guid_from_file(gid, "/etc/group"), size, datetime, color, name_display, reset_color
-            );
+            )?;
         } else {
-            print!("{}{}{}  ", color, name_display, reset_color);
+            write!(out, "{}{}{}  ", color, name_display, reset_color)?;
         }
     }
 
     if !long_format {
-        println!();
+        writeln!(out)?;
     }
 
     Ok(())
@@ -1,11 +1,14 @@
 use crate::utils::error::ShellError;
 use crate::utils::messages::NO_DIRECTORY_SPECIFIED;
-use std::fs::create_dir;
+use std::fs::{create_dir, create_dir_all};
+use std::io::Error;
 use std::path::Path;
 
 /**
  * Create directories.
  *
+ * With `-p`, missing parent directories are created too and existing directories are not an error.
+ *
  * # Arguments
  * * `current_dir` - The current directory.
  * * `args` - The arguments passed to the command.
@@ -22,13 +25,16 @@ use std::path::Path;
  * ```
 */
 pub fn mkdir(current_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
+    let parents = args.first() == Some(&"-p");
+    let args = if parents { &args[1..] } else { args };
     if args.is_empty() {
         return Err(ShellError::InvalidArguments(format!("mkdir: {NO_DIRECTORY_SPECIFIED}")));
     }
 
     for dir_name in args {
         let new_dir = current_dir.join(dir_name);
-        create_dir(&new_dir).map_err(|e| ShellError::InvalidArguments(format!("mkdir: {}", e)))?;
+        let result = if parents { create_dir_all(&new_dir) } else { create_dir(&new_dir) };
+        result.map_err(|e| ShellError::IoError(Error::new(e.kind(), format!("mkdir: {}", e))))?;
     }
 
     Ok(())
@@ -7,4 +7,15 @@ pub(crate) mod cp;
 pub(crate) mod mv;
 pub(crate) mod rm;
 pub(crate) mod exit;
-pub(crate) mod touch;
\ No newline at end of file
+pub(crate) mod r#return;
+pub(crate) mod touch;
+pub(crate) mod set;
+pub(crate) mod export;
+pub(crate) mod unset;
+pub(crate) mod env;
+pub(crate) mod r#let;
+pub(crate) mod history;
+pub(crate) mod alias;
+pub(crate) mod local;
+pub(crate) mod loop_control;
+pub(crate) mod source;
\ No newline at end of file
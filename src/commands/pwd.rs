@@ -1,4 +1,5 @@
 use crate::utils::error::ShellError;
+use std::io::Write;
 use std::path::Path;
 
 /**
@@ -6,18 +7,20 @@ use std::path::Path;
  *
  * # Arguments
  * * `current_dir` - The current directory.
+ * * `out` - The stream the directory is written to.
  *
  * # Examples
  * ```
+ * use std::io::stdout;
  * use std::path::Path;
  * use shell::commands::pwd;
  *
  * let current_dir = Path::new("/tmp");
- * let result = pwd(current_dir);
+ * let result = pwd(current_dir, &mut stdout());
  * assert!(result.is_ok());
  * ```
 */
-pub fn pwd(current_dir: &Path) -> Result<(), ShellError> {
-    println!("{}", current_dir.display());
+pub fn pwd(current_dir: &Path, out: &mut dyn Write) -> Result<(), ShellError> {
+    writeln!(out, "{}", current_dir.display())?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{MANY_ARGS, NUMERIC_ARGUMENT_REQUIRED, RETURN_OUTSIDE_FUNCTION};
+use std::io::{self, Write};
+
+/**
+ * Return command
+ *
+ * This command is used to leave the function or the file run by `source`, with the given status
+ * or the status of the last command. Like `exit`, it never returns `Ok`: the `ShellError::Return`
+ * error unwinds up to the function call or the `source` command.
+ *
+ * # Arguments
+ * * `last_status` - The status of the last command, used when no status is given.
+ * * `in_function` - Whether a function or a file run by `source` is being run.
+ * * `args` - The arguments passed to the command.
+ * * `err` - The stream errors are written to.
+ */
+pub fn r#return(last_status: i32, in_function: bool, args: &[&str], err: &mut dyn Write) -> Result<(), ShellError> {
+    if !in_function {
+        return Err(ShellError::IoError(io::Error::other(format!("return: {RETURN_OUTSIDE_FUNCTION}"))));
+    }
+    if args.len() > 1 {
+        return Err(ShellError::IoError(io::Error::other(format!("return: {MANY_ARGS}"))));
+    }
+
+    let code = match args.first() {
+        None => last_status,
+        Some(arg) => match arg.parse::<i64>() {
+            Ok(code) => code.rem_euclid(256) as i32,
+            Err(_) => {
+                writeln!(err, "\x1b[31mreturn: {}: {NUMERIC_ARGUMENT_REQUIRED}\x1b[0m", arg)?;
+                2
+            }
+        },
+    };
+
+    Err(ShellError::Return(code))
+}
@@ -1,6 +1,7 @@
 use crate::utils::error::ShellError;
 use crate::utils::messages::{CANNOT_REMOVE, IS_A_DIRECTORY, NOT_SPECIFIED};
 use std::fs::{remove_dir_all, remove_file};
+use std::io::Error;
 use std::path::Path;
 
 /**
@@ -43,7 +44,7 @@ pub fn rm(current_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
         if is_recursive {
             remove_dir_all(&path)?;
         } else {
-            remove_file(&path).map_err(|_| ShellError::InvalidArguments(format!("rm: {CANNOT_REMOVE} '{}': {IS_A_DIRECTORY}", file)))?;
+            remove_file(&path).map_err(|e| ShellError::IoError(Error::new(e.kind(), format!("rm: {CANNOT_REMOVE} '{}': {IS_A_DIRECTORY}", file))))?;
         }
     }
 
@@ -0,0 +1,53 @@
+use crate::shell::ShellOptions;
+use crate::utils::error::ShellError;
+use crate::utils::messages::INVALID_OPTION_NAME;
+use std::io::Write;
+
+/**
+ * Set or unset shell options.
+ *
+ * `set -o name` enables an option, `set +o name` disables it and `set -o` lists them.
+ *
+ * # Arguments
+ * * `options` - The options of the shell.
+ * * `args` - The arguments passed to the command.
+ * * `out` - The stream the option list is written to.
+ *
+ * # Example
+ * ```rust
+ * use std::io::stdout;
+ * use shell::commands::set;
+ * use shell::shell::ShellOptions;
+ *
+ * let mut options = ShellOptions::default();
+ * set(&mut options, &["-o", "pipefail"], &mut stdout());
+ * assert!(options.pipefail);
+ * ```
+ */
+pub fn set(options: &mut ShellOptions, args: &[&str], out: &mut dyn Write) -> Result<(), ShellError> {
+    if args.is_empty() || args == ["-o"] || args == ["+o"] {
+        for (name, enabled) in options.list() {
+            writeln!(out, "{:<15}{}", name, if enabled { "on" } else { "off" })?;
+        }
+        return Ok(());
+    }
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let enable = match *arg {
+            "-o" => true,
+            "+o" => false,
+            _ => return Err(ShellError::InvalidArguments(format!("set: {}: {INVALID_OPTION_NAME}", arg))),
+        };
+
+        let name = args.next().ok_or_else(|| {
+            ShellError::InvalidArguments(format!("set: {}: {INVALID_OPTION_NAME}", arg))
+        })?;
+        let option = options.get_mut(name).ok_or_else(|| {
+            ShellError::InvalidArguments(format!("set: {}: {INVALID_OPTION_NAME}", name))
+        })?;
+        *option = enable;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::exec::function::MAX_FUNCTION_DEPTH;
+use crate::exec::script::run_file;
+use crate::exec::stream::Streams;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::messages::{FILENAME_REQUIRED, FILE_NOT_FOUND, MAX_SOURCE_NESTING_EXCEEDED};
+use std::env;
+use std::io::{self, Error, ErrorKind};
+use std::path::PathBuf;
+
+/**
+ * Run the commands of a file in the current shell, so that the variables, aliases, functions
+ * and directory it sets are kept, as `source file [args...]` or `. file [args...]`.
+ *
+ * A name without a slash is looked up in `PATH`, then in the current directory.
+ * The arguments after the file become its positional parameters until it is done,
+ * and `return` stops the file with the given status.
+ *
+ * The files being run count with the functions being run against `MAX_FUNCTION_DEPTH`, so that
+ * a file sourcing itself fails instead of overflowing the stack.
+ *
+ * Returns the status of the last command of the file.
+ *
+ * # Arguments
+ * * `shell` - The shell running the file.
+ * * `name` - The name the command was called with, `source` or `.`.
+ * * `args` - The arguments passed to the command.
+ * * `streams` - The streams of the commands of the file.
+ *
+ * # Example
+ * ```rust
+ * use shell::commands::source;
+ * use shell::exec::stream::Streams;
+ * use shell::shell::Shell;
+ *
+ * let mut shell = Shell::new();
+ * source(&mut shell, "source", &["env.sh"], &Streams::inherit());
+ * ```
+ */
+pub fn source(shell: &mut Shell, name: &str, args: &[&str], streams: &Streams) -> Result<i32, ShellError> {
+    let Some(file) = args.first() else {
+        return Err(ShellError::InvalidArguments(format!(
+            "{}: {FILENAME_REQUIRED}\n{}: usage: {} filename [arguments]",
+            name, name, name
+        )));
+    };
+    let Some(path) = find_file(shell, file) else {
+        return Err(ShellError::IoError(Error::new(
+            ErrorKind::NotFound,
+            format!("{}: {}: {FILE_NOT_FOUND}", name, file),
+        )));
+    };
+
+    if shell.sourcing + shell.locals.len() >= MAX_FUNCTION_DEPTH {
+        let message = format!("{}: {}: {MAX_SOURCE_NESTING_EXCEEDED} ({MAX_FUNCTION_DEPTH})", name, file);
+        return Err(ShellError::IoError(io::Error::other(message)));
+    }
+
+    let positional = (args.len() > 1).then(|| {
+        std::mem::replace(&mut shell.positional, args[1..].iter().map(|arg| arg.to_string()).collect())
+    });
+    shell.sourcing += 1;
+
+    let result = run_file(shell, &path, streams);
+
+    shell.sourcing -= 1;
+    if let Some(positional) = positional {
+        shell.positional = positional;
+    }
+
+    match result {
+        Err(ShellError::Return(status)) => Ok(status),
+        result => result,
+    }
+}
+
+/**
+ * Find the file to run: a path when the name holds a slash, or else a file of `PATH`
+ * or of the current directory.
+ */
+fn find_file(shell: &Shell, name: &str) -> Option<PathBuf> {
+    if name.contains('/') {
+        return Some(shell.current_dir().join(name)).filter(|path| path.is_file());
+    }
+
+    let path = shell.variables.get("PATH").unwrap_or_default();
+    env::split_paths(path)
+        .map(|dir| shell.current_dir().join(dir).join(name))
+        .chain([shell.current_dir().join(name)])
+        .find(|path| path.is_file())
+}
@@ -41,6 +41,7 @@ pub fn touch(current_dir: &Path, args: &[&str]) -> Result<(), ShellError> {
         } else {
             OpenOptions::new()
                 .create(true)
+                .truncate(false)
                 .write(true)
                 .open(&file_path)
                 .map_err(|e| ShellError::IoError(Error::new(
@@ -0,0 +1,37 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::NOT_A_VALID_IDENTIFIER;
+use crate::variables::{is_valid_name, Variables};
+
+/**
+ * Remove variables.
+ *
+ * # Arguments
+ * * `variables` - The variables of the shell.
+ * * `args` - The names of the variables, optionally preceded by `-v`.
+ *
+ * # Example
+ * ```rust
+ * use shell::commands::unset;
+ * use shell::variables::Variables;
+ *
+ * let mut variables = Variables::default();
+ * variables.set("TMP_DIR", "/tmp");
+ * unset(&mut variables, &["TMP_DIR"]);
+ * assert_eq!(variables.get("TMP_DIR"), None);
+ * ```
+ */
+pub fn unset(variables: &mut Variables, args: &[&str]) -> Result<(), ShellError> {
+    let names = match args.first() {
+        Some(&"-v") => &args[1..],
+        _ => args,
+    };
+
+    for name in names {
+        if !is_valid_name(name) {
+            return Err(ShellError::InvalidArguments(format!("unset: `{}': {NOT_A_VALID_IDENTIFIER}", name)));
+        }
+        variables.unset(name);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use crate::exec::external::is_executable;
+use crate::shell::{Shell, BUILTINS};
+use crate::utils::path::get_home_dir;
+use std::env;
+use std::fs::read_dir;
+use std::path::Path;
+
+/**
+ * The flags known for each builtin, completed after a `-`.
+ */
+const FLAGS: &[(&str, &[&str])] = &[
+    ("ls", &["-a", "-l", "-F"]),
+    ("mkdir", &["-p"]),
+    ("rm", &["-r"]),
+    ("set", &["-o", "+o"]),
+    ("export", &["-p"]),
+    ("unset", &["-v"]),
+    ("env", &["-i"]),
+    ("history", &["-c", "-d"]),
+    ("alias", &["-p"]),
+    ("unalias", &["-a"]),
+];
+
+/**
+ * The characters that end a command, after which a command name is expected.
+ */
+const COMMAND_SEPARATORS: &[char] = &['|', '&', ';', '(', '\n'];
+
+/**
+ * The characters escaped with a backslash when inserted in the line.
+ */
+const SPECIAL_CHARS: &[char] = &[
+    ' ', '\t', '\'', '"', '\\', '$', '`', '&', '|', ';', '<', '>', '(', ')', '*', '?', '[', ']', '{', '}', '!', '#',
+];
+
+/**
+ * A possible completion of the word before the cursor.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The text replacing the word.
+    pub text: String,
+    /// The name shown when listing the candidates.
+    pub display: String,
+    /// Whether the word is complete and is followed by a space, unlike a directory.
+    pub complete: bool,
+}
+
+/**
+ * The completions of the word before the cursor, which starts at `start` in the line.
+ */
+pub struct Completion {
+    pub start: usize,
+    pub candidates: Vec<Candidate>,
+}
+
+/**
+ * Complete the word before the cursor.
+ *
+ * The word is completed with:
+ * * builtins, aliases, functions and programs of `PATH` in the place of a command name,
+ * * variable names after `$` or `${`,
+ * * the known flags of a builtin after `-`, and the options after `set -o`,
+ * * paths relative to the current directory otherwise, only directories after `cd`.
+ *
+ * # Arguments
+ * * `shell` - The shell, for its current directory, variables and options.
+ * * `line` - The characters of the line.
+ * * `cursor` - The position of the cursor in the line.
+ */
+pub fn complete(shell: &Shell, line: &[char], cursor: usize) -> Completion {
+    let mut start = cursor;
+    while start > 0 && !is_word_boundary(line, start - 1) {
+        start -= 1;
+    }
+    let word: String = line[start..cursor].iter().collect();
+
+    // The words of the current command before the one being completed.
+    let before: String = line[..start].iter().collect();
+    let command = &before[before.rfind(COMMAND_SEPARATORS).map_or(0, |i| i + 1)..];
+    let previous: Vec<&str> = command.split_whitespace().collect();
+
+    let mut candidates = if let Some(dollar) = word.rfind('$') {
+        start += word[..dollar].chars().count();
+        complete_variable(shell, &word[dollar..])
+    } else if previous.is_empty() {
+        if word.contains('/') {
+            complete_path(shell, &word, |path| path.is_dir() || is_executable(path))
+        } else {
+            complete_command(shell, &word)
+        }
+    } else if previous[0] == "set" && matches!(previous.last(), Some(&("-o" | "+o"))) {
+        let options = shell.options.list();
+        let names = options.iter().map(|(name, _)| name.to_string());
+        complete_words(names, &word)
+    } else if word.starts_with('-') {
+        let flags = FLAGS.iter().find(|(name, _)| *name == previous[0]).map_or(&[][..], |(_, flags)| flags);
+        complete_words(flags.iter().map(|flag| flag.to_string()), &word)
+    } else if previous[0] == "cd" {
+        complete_path(shell, &word, Path::is_dir)
+    } else {
+        complete_path(shell, &word, |_| true)
+    };
+
+    candidates.sort_by(|a, b| a.display.cmp(&b.display));
+    candidates.dedup();
+    Completion { start, candidates }
+}
+
+/**
+ * Get the longest prefix shared by every candidate.
+ */
+pub fn common_prefix(candidates: &[Candidate]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+
+    let mut len = first.text.len();
+    for candidate in &candidates[1..] {
+        len = first.text[..len]
+            .char_indices()
+            .zip(candidate.text.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(candidate.text.len()), |((i, _), _)| i);
+    }
+    first.text[..len].to_owned()
+}
+
+fn is_word_boundary(line: &[char], i: usize) -> bool {
+    let escaped = i > 0 && line[i - 1] == '\\';
+    !escaped && (line[i].is_whitespace() || COMMAND_SEPARATORS.contains(&line[i]) || matches!(line[i], '<' | '>'))
+}
+
+fn complete_words(words: impl Iterator<Item = String>, prefix: &str) -> Vec<Candidate> {
+    words
+        .filter(|word| word.starts_with(prefix))
+        .map(|word| Candidate {
+            text: word.clone(),
+            display: word,
+            complete: true,
+        })
+        .collect()
+}
+
+/**
+ * Complete a command name with the builtins, the aliases, the functions and the executables found in `PATH`.
+ */
+fn complete_command(shell: &Shell, prefix: &str) -> Vec<Candidate> {
+    let mut names: Vec<String> = BUILTINS.iter().map(|name| name.to_string()).collect();
+    names.extend(shell.aliases.keys().cloned());
+    names.extend(shell.functions.keys().cloned());
+    let path = shell.variables.get("PATH").unwrap_or_default();
+    for dir in env::split_paths(path) {
+        let Ok(entries) = read_dir(&dir) else {
+            continue;
+        };
+        names.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .filter(|name| name.starts_with(prefix) && is_executable(&dir.join(name))),
+        );
+    }
+
+    complete_words(names.into_iter(), prefix)
+}
+
+/**
+ * Complete a variable name, in a word starting with `$` or `${`.
+ */
+fn complete_variable(shell: &Shell, word: &str) -> Vec<Candidate> {
+    let (open, prefix, close) = match word.strip_prefix("${") {
+        Some(prefix) => ("${", prefix, "}"),
+        None => ("$", &word[1..], ""),
+    };
+
+    shell
+        .variables
+        .names()
+        .filter(|name| name.starts_with(prefix))
+        .map(|name| Candidate {
+            text: format!("{}{}{}", open, name, close),
+            display: name.to_owned(),
+            complete: true,
+        })
+        .collect()
+}
+
+/**
+ * Complete a path relative to the current directory, keeping the entries accepted by `filter`.
+ *
+ * Directories end with a `/` so that the completion can go on inside them. Hidden entries
+ * are only completed when the name being completed starts with a dot.
+ */
+fn complete_path(shell: &Shell, word: &str, filter: impl Fn(&Path) -> bool) -> Vec<Candidate> {
+    let word = unescape(word);
+    let (dir, prefix) = match word.rfind('/') {
+        Some(slash) => word.split_at(slash + 1),
+        None => ("", word.as_str()),
+    };
+
+    let expanded = match dir.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match get_home_dir(&shell.variables) {
+            Ok(home) => format!("{}{}", home.display(), rest),
+            Err(_) => dir.to_owned(),
+        },
+        _ => dir.to_owned(),
+    };
+    let Ok(entries) = read_dir(shell.current_dir().join(if expanded.is_empty() { "." } else { &expanded })) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+
+            let path = entry.path();
+            if !filter(&path) {
+                return None;
+            }
+            let is_dir = path.is_dir();
+            let suffix = if is_dir { "/" } else { "" };
+            Some(Candidate {
+                text: format!("{}{}{}", escape(dir), escape(&name), suffix),
+                display: format!("{}{}", name, suffix),
+                complete: !is_dir,
+            })
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if SPECIAL_CHARS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            '\'' | '"' => {}
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
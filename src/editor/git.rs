@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::fs::{metadata, read, read_to_string, symlink_metadata};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/**
+ * How many characters of a commit hash are shown for a detached HEAD.
+ */
+const SHORT_HASH_LEN: usize = 7;
+
+/**
+ * The flags of an index entry whose file is not compared with the working tree.
+ */
+const ASSUME_VALID: u16 = 0x8000;
+const EXTENDED: u16 = 0x4000;
+const SKIP_WORKTREE: u16 = 0x4000;
+
+/**
+ * The file mode of a submodule in the index.
+ */
+const GITLINK_MODE: u32 = 0o160000;
+
+/**
+ * A git repository found above a directory.
+ */
+#[derive(Clone)]
+struct Repository {
+    /// The directory holding `HEAD` and the index, `.git` or the directory of a linked worktree.
+    git_dir: PathBuf,
+    /// The directory holding the refs and the config, shared by every worktree.
+    common_dir: PathBuf,
+    work_tree: PathBuf,
+    /// The `.git` directory or file found in the work tree.
+    dot_git: PathBuf,
+}
+
+/**
+ * What is needed from an entry of the index to tell whether its file changed.
+ */
+struct IndexEntry {
+    path: String,
+    mtime: (u32, u32),
+    size: u32,
+}
+
+/**
+ * The git segment of the prompt, showing the branch of the repository of the current directory.
+ *
+ * The segment is read from the files of the repository, without running git. The repository of
+ * every directory and the parsed index of every repository are cached, and a repository found dirty
+ * stays so until its index changes, so that large repositories only cost one `stat` per tracked file
+ * at the prompts where they are clean.
+ */
+#[derive(Default)]
+pub struct GitPrompt {
+    repositories: HashMap<PathBuf, Repository>,
+    /// The entries of each index, with the modification time and size of the index they were read from.
+    indexes: HashMap<PathBuf, (IndexStamp, Vec<IndexEntry>)>,
+    /// The indexes of the repositories found dirty, with their stamp at that time.
+    dirty: HashMap<PathBuf, IndexStamp>,
+}
+
+/**
+ * The modification time and size of an index, which change whenever git writes it.
+ */
+type IndexStamp = (SystemTime, u64);
+
+impl GitPrompt {
+    /**
+     * Get the segment for a directory, such as ` (main *↑)`, or an empty string outside repositories.
+     *
+     * The segment holds the branch name, or `detached` and the short hash of the commit,
+     * then `*` when a tracked file changed in the work tree, and `↑`, `↓` or `↕` when the branch is ahead of,
+     * behind or diverged from its upstream branch.
+     *
+     * # Arguments
+     * * `dir` - The current directory of the shell.
+     *
+     * # Example
+     * ```rust
+     * use std::path::Path;
+     * use shell::editor::git::GitPrompt;
+     *
+     * let mut git = GitPrompt::default();
+     * println!("~/src/0-shell{} $ ", git.segment(Path::new("/home/user/src/0-shell")));
+     * ```
+     */
+    pub fn segment(&mut self, dir: &Path) -> String {
+        let Some(repository) = self.repository(dir) else {
+            return String::new();
+        };
+        let Ok(head) = read_to_string(repository.git_dir.join("HEAD")) else {
+            return String::new();
+        };
+        let head = head.trim();
+
+        let mut segment = match head.strip_prefix("ref: ") {
+            Some(reference) => reference.strip_prefix("refs/heads/").unwrap_or(reference).to_owned(),
+            None => format!("detached {}", head.get(..SHORT_HASH_LEN).unwrap_or(head)),
+        };
+
+        let mut markers = String::new();
+        if self.is_dirty(&repository) {
+            markers.push('*');
+        }
+        if let Some(branch) = head.strip_prefix("ref: refs/heads/") {
+            markers.push_str(upstream_marker(&repository, branch));
+        }
+        if !markers.is_empty() {
+            segment.push(' ');
+            segment.push_str(&markers);
+        }
+
+        format!(" ({})", segment)
+    }
+
+    /**
+     * Find the repository of a directory, looking for `.git` in it and its parents.
+     *
+     * A cached repository is used while its `.git` entry and git directory still exist,
+     * and no `.git` was created between the directory and its work tree, as by `git init`.
+     */
+    fn repository(&mut self, dir: &Path) -> Option<Repository> {
+        if let Some(repository) = self.repositories.get(dir) {
+            let nested = dir
+                .ancestors()
+                .take_while(|&ancestor| ancestor != repository.work_tree)
+                .any(|ancestor| symlink_metadata(ancestor.join(".git")).is_ok());
+            if !nested && symlink_metadata(&repository.dot_git).is_ok() && repository.git_dir.is_dir() {
+                return Some(repository.clone());
+            }
+        }
+
+        let repository = dir.ancestors().find_map(|ancestor| {
+            let dot_git = ancestor.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else {
+                // Linked worktrees and submodules have a `.git` file pointing to their git directory.
+                let content = read_to_string(&dot_git).ok()?;
+                ancestor.join(content.trim().strip_prefix("gitdir:")?.trim())
+            };
+            let common_dir = match read_to_string(git_dir.join("commondir")) {
+                Ok(common_dir) => git_dir.join(common_dir.trim()),
+                Err(_) => git_dir.clone(),
+            };
+
+            Some(Repository {
+                git_dir,
+                common_dir,
+                work_tree: ancestor.to_path_buf(),
+                dot_git: ancestor.join(".git"),
+            })
+        })?;
+
+        self.repositories.insert(dir.to_path_buf(), repository.clone());
+        Some(repository)
+    }
+
+    /**
+     * Check whether a tracked file was changed or removed, comparing its size and modification time
+     * with the ones recorded in the index, as git does before looking at the content.
+     *
+     * Staged changes are not shown: telling them apart needs the tree of `HEAD`, which is compressed.
+     *
+     * A file that differs from the index keeps differing until git writes the index again, so a dirty
+     * result is kept for as long as the index has the same stamp.
+     */
+    fn is_dirty(&mut self, repository: &Repository) -> bool {
+        let index_path = repository.git_dir.join("index");
+        let Ok(index_metadata) = metadata(&index_path) else {
+            return false;
+        };
+        let stamp = (index_metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), index_metadata.len());
+        if self.dirty.get(&index_path) == Some(&stamp) {
+            return true;
+        }
+
+        let cached = self.indexes.get(&index_path).filter(|(cached_stamp, _)| *cached_stamp == stamp);
+        let entries = match cached {
+            Some((_, entries)) => entries,
+            None => {
+                let sha256 = config_section(repository, "extensions")
+                    .get("objectformat")
+                    .is_some_and(|format| format == "sha256");
+                let hash_len = if sha256 { 32 } else { 20 };
+                let entries = read(&index_path).ok().and_then(|data| parse_index(&data, hash_len)).unwrap_or_default();
+                self.indexes.insert(index_path.clone(), (stamp, entries));
+                &self.indexes[&index_path].1
+            }
+        };
+
+        let dirty = entries.iter().any(|entry| match symlink_metadata(repository.work_tree.join(&entry.path)) {
+            Ok(file) => {
+                let nanos = if entry.mtime.1 == 0 { 0 } else { file.mtime_nsec() as u32 };
+                file.size() as u32 != entry.size || (file.mtime() as u32, nanos) != entry.mtime
+            }
+            Err(_) => true,
+        });
+        if dirty {
+            self.dirty.insert(index_path, stamp);
+        } else {
+            self.dirty.remove(&index_path);
+        }
+        dirty
+    }
+}
+
+/**
+ * Parse the entries of an index file, in the versions 2 to 4 of its format.
+ *
+ * The hashes of the entries are 20 bytes long, or 32 bytes in SHA-256 repositories.
+ *
+ * Returns `None` if the file is not a valid index.
+ */
+fn parse_index(data: &[u8], hash_len: usize) -> Option<Vec<IndexEntry>> {
+    let u32_at = |pos: usize| -> Option<u32> { Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?)) };
+    let u16_at = |pos: usize| -> Option<u16> { Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?)) };
+
+    if data.get(..4)? != b"DIRC" {
+        return None;
+    }
+    let version = u32_at(4)?;
+    let count = u32_at(8)?;
+
+    let mut entries = Vec::with_capacity((count as usize).min(data.len() / 62));
+    let mut pos = 12;
+    let mut previous = String::new();
+    for _ in 0..count {
+        let start = pos;
+        let mtime = (u32_at(pos + 8)?, u32_at(pos + 12)?);
+        let mode = u32_at(pos + 24)?;
+        let size = u32_at(pos + 36)?;
+        pos += 40 + hash_len;
+        let flags = u16_at(pos)?;
+        pos += 2;
+        let extended = if version >= 3 && flags & EXTENDED != 0 {
+            pos += 2;
+            u16_at(pos - 2)?
+        } else {
+            0
+        };
+
+        let path = if version >= 4 {
+            // The path is stored as the number of bytes to remove from the previous path, then a suffix.
+            let (strip, len) = read_varint(data.get(pos..)?)?;
+            pos += len;
+            let end = pos + data.get(pos..)?.iter().position(|&byte| byte == 0)?;
+            let mut path = previous.clone();
+            path.truncate(path.len().checked_sub(strip)?);
+            path.push_str(&String::from_utf8_lossy(&data[pos..end]));
+            pos = end + 1;
+            path
+        } else {
+            let end = pos + data.get(pos..)?.iter().position(|&byte| byte == 0)?;
+            let path = String::from_utf8_lossy(&data[pos..end]).into_owned();
+            // Entries are padded with 1 to 8 null bytes to a multiple of 8 bytes.
+            pos = start + (end - start + 8) / 8 * 8;
+            path
+        };
+
+        previous.clone_from(&path);
+        if flags & ASSUME_VALID == 0 && extended & SKIP_WORKTREE == 0 && mode != GITLINK_MODE {
+            entries.push(IndexEntry { path, mtime, size });
+        }
+    }
+
+    Some(entries)
+}
+
+/**
+ * Read the variable-length integer of the index format 4, returning its value and its size in bytes.
+ */
+fn read_varint(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, &byte) in data.iter().enumerate() {
+        value = (value << 7) | (byte & 0x7f) as usize;
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+        value += 1;
+    }
+    None
+}
+
+/**
+ * Get the commit a ref points to, from its file or from `packed-refs`.
+ */
+fn resolve_ref(repository: &Repository, name: &str) -> Option<String> {
+    if let Ok(content) = read_to_string(repository.common_dir.join(name)) {
+        let content = content.trim();
+        return match content.strip_prefix("ref: ") {
+            Some(target) => resolve_ref(repository, target),
+            None => Some(content.to_owned()),
+        };
+    }
+
+    let packed = read_to_string(repository.common_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| match line.split_once(' ') {
+        Some((hash, reference)) if reference == name => Some(hash.to_owned()),
+        _ => None,
+    })
+}
+
+/**
+ * Get the entries of a section of the config of a repository, such as `[branch "main"]`,
+ * with their keys in lowercase.
+ */
+fn config_section(repository: &Repository, section: &str) -> HashMap<String, String> {
+    let config = read_to_string(repository.common_dir.join("config")).unwrap_or_default();
+    let header = format!("[{}]", section);
+    let mut entries = HashMap::new();
+    let mut in_section = false;
+
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case(&header);
+        } else if let Some((key, value)) = line.split_once('=').filter(|_| in_section) {
+            entries.insert(key.trim().to_lowercase(), value.trim().to_owned());
+        }
+    }
+
+    entries
+}
+
+/**
+ * Get the ref of the upstream branch of a branch, from the `branch.<name>.remote` and
+ * `branch.<name>.merge` entries of the config.
+ */
+fn upstream_ref(repository: &Repository, branch: &str) -> Option<String> {
+    let section = config_section(repository, &format!("branch \"{}\"", branch));
+    let merge = section.get("merge")?;
+    match section.get("remote")?.as_str() {
+        "." => Some(merge.clone()),
+        remote => Some(format!("refs/remotes/{}/{}", remote, merge.strip_prefix("refs/heads/")?)),
+    }
+}
+
+/**
+ * Compare a branch with its upstream branch.
+ *
+ * Commits cannot be read without decompressing objects, so the reflogs are used instead:
+ * the branch is behind when its upstream once pointed to the commit of the branch, and ahead
+ * when the branch once pointed to the commit of its upstream.
+ */
+fn upstream_marker(repository: &Repository, branch: &str) -> &'static str {
+    let local_ref = format!("refs/heads/{}", branch);
+    let Some(upstream_ref) = upstream_ref(repository, branch) else {
+        return "";
+    };
+    let (Some(local), Some(upstream)) = (resolve_ref(repository, &local_ref), resolve_ref(repository, &upstream_ref)) else {
+        return "";
+    };
+
+    let reached = |reference: &str, hash: &str| {
+        read_to_string(repository.common_dir.join("logs").join(reference))
+            .is_ok_and(|log| log.lines().any(|line| line.split(' ').nth(1) == Some(hash)))
+    };
+    // A branch reset behind its upstream still has the commit of the upstream in its reflog,
+    // so the upstream is looked at first.
+    if local == upstream {
+        ""
+    } else if reached(&upstream_ref, &local) {
+        "↓"
+    } else if reached(&local_ref, &upstream) {
+        "↑"
+    } else {
+        "↕"
+    }
+}
@@ -0,0 +1,241 @@
+use crate::utils::path::get_home_dir;
+use crate::variables::Variables;
+use std::fs::{metadata, read_to_string, rename, write, File, OpenOptions};
+use std::io::Write;
+use std::os::raw::c_int;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/**
+ * The operation of `flock` taking an exclusive lock.
+ */
+const LOCK_EX: c_int = 2;
+
+extern "C" {
+    fn flock(fd: c_int, operation: c_int) -> c_int;
+}
+
+/**
+ * The name of the history file in the home directory, used when `HISTFILE` is not set.
+ */
+const HISTORY_FILE: &str = ".0shell_history";
+
+/**
+ * How many entries are kept in memory and in the file, when `HISTSIZE` and `HISTFILESIZE` are not set.
+ */
+const DEFAULT_SIZE: usize = 1000;
+const DEFAULT_FILE_SIZE: usize = 2000;
+
+/**
+ * The command lines entered in the shell.
+ *
+ * Every new entry is appended to the history file right away, so that several shells can share
+ * the same file without overwriting the entries of each other. The file is locked while an entry
+ * is appended or the file is rewritten, so that no entry is lost when both happen at once.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /**
+     * Load the history from a file, which is shortened to `HISTFILESIZE` entries if it grew bigger.
+     *
+     * # Arguments
+     * * `path` - The history file, which may not exist yet.
+     * * `variables` - The variables of the shell, holding the size limits.
+     */
+    pub fn load(path: PathBuf, variables: &Variables) -> History {
+        let mut entries = read_entries(&path);
+
+        let file_size = limit(variables, "HISTFILESIZE", DEFAULT_FILE_SIZE);
+        if entries.len() > file_size {
+            entries.drain(..entries.len() - file_size);
+            edit_file(&path, |entries| {
+                entries.drain(..entries.len().saturating_sub(file_size));
+            });
+        }
+
+        let size = limit(variables, "HISTSIZE", DEFAULT_SIZE);
+        if entries.len() > size {
+            entries.drain(..entries.len() - size);
+        }
+
+        History {
+            entries,
+            path: Some(path),
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /**
+     * Add a command line to the history and to the history file.
+     *
+     * `HISTCONTROL` can skip some lines: `ignorespace` skips the lines starting with a space,
+     * `ignoredups` skips a line equal to the previous one, `ignoreboth` does both, and `erasedups`
+     * removes the previous copies of the line from the history.
+     */
+    pub fn add(&mut self, line: &str, variables: &Variables) {
+        if line.trim().is_empty() {
+            return;
+        }
+
+        let control = variables.get("HISTCONTROL").unwrap_or_default();
+        let control: Vec<&str> = control.split(':').collect();
+        let has = |option: &str| control.contains(&option) || control.contains(&"ignoreboth");
+        if has("ignorespace") && line.starts_with(' ') {
+            return;
+        }
+        if has("ignoredups") && self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        if control.contains(&"erasedups") && self.entries.iter().any(|entry| entry == line) {
+            self.entries.retain(|entry| entry != line);
+            self.edit_file(|entries| entries.retain(|entry| entry != line));
+        }
+
+        self.entries.push(line.to_owned());
+        let size = limit(variables, "HISTSIZE", DEFAULT_SIZE);
+        if self.entries.len() > size {
+            self.entries.drain(..self.entries.len() - size);
+        }
+
+        if let Some(mut file) = self.path.as_deref().and_then(lock) {
+            let _ = file.write_all(encode(line).as_bytes());
+        }
+    }
+
+    /**
+     * Remove every entry of the history, leaving the history file shared with other shells as it is.
+     */
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /**
+     * Remove the entry at `index`, counted from 0, from the history and from the history file.
+     *
+     * The file may hold older entries and the ones of other shells, so the copy of the entry removed
+     * from it is the one with as many copies after it as in the history.
+     *
+     * Returns `false` if there is no such entry.
+     */
+    pub fn delete(&mut self, index: usize) -> bool {
+        if index >= self.entries.len() {
+            return false;
+        }
+        let entry = self.entries.remove(index);
+        let later = self.entries[index..].iter().filter(|&later| *later == entry).count();
+        self.edit_file(|entries| {
+            let mut copies = entries.iter().enumerate().rev().filter(|(_, copy)| **copy == entry);
+            if let Some(position) = copies.nth(later).map(|(position, _)| position) {
+                entries.remove(position);
+            }
+        });
+        true
+    }
+
+    /**
+     * Change the entries of the history file, when there is one.
+     */
+    fn edit_file(&self, edit: impl FnOnce(&mut Vec<String>)) {
+        if let Some(path) = &self.path {
+            edit_file(path, edit);
+        }
+    }
+}
+
+/**
+ * Get the path of the history file: `HISTFILE`, or `~/.0shell_history`.
+ */
+pub fn history_path(variables: &Variables) -> Option<PathBuf> {
+    match variables.get("HISTFILE") {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        Some(_) => None,
+        None => get_home_dir(variables).ok().map(|home| home.join(HISTORY_FILE)),
+    }
+}
+
+/**
+ * Write an entry as a line of the history file.
+ *
+ * The newlines of a command written over several lines, inside quotes, are escaped with a backslash
+ * at the end of the line, as a command never ends with an odd number of backslashes.
+ */
+fn encode(entry: &str) -> String {
+    format!("{}\n", entry.replace('\n', "\\\n"))
+}
+
+/**
+ * Read the entries of a history file, one per line except for the lines ending with an escaped newline.
+ */
+fn read_entries(path: &Path) -> Vec<String> {
+    let content = read_to_string(path).unwrap_or_default();
+    let mut entries = Vec::new();
+    let mut entry = String::new();
+    for line in content.lines() {
+        let backslashes = line.chars().rev().take_while(|&c| c == '\\').count();
+        if backslashes % 2 == 1 {
+            entry.push_str(&line[..line.len() - 1]);
+            entry.push('\n');
+            continue;
+        }
+        entry.push_str(line);
+        if !entry.is_empty() {
+            entries.push(std::mem::take(&mut entry));
+        }
+    }
+    if !entry.is_empty() {
+        entries.push(entry);
+    }
+
+    entries
+}
+
+/**
+ * Open a history file to append to it, with an exclusive lock released when the file is closed.
+ *
+ * A shell waiting for the lock while another one rewrites the file ends up with the replaced file,
+ * so the file is opened again until the locked file is still the one at the path.
+ */
+fn lock(path: &Path) -> Option<File> {
+    loop {
+        let file = OpenOptions::new().create(true).append(true).open(path).ok()?;
+        if unsafe { flock(file.as_raw_fd(), LOCK_EX) } != 0 {
+            return None;
+        }
+        let (locked, current) = (file.metadata().ok()?, metadata(path).ok()?);
+        if (locked.dev(), locked.ino()) == (current.dev(), current.ino()) {
+            return Some(file);
+        }
+    }
+}
+
+/**
+ * Change the entries of a history file, holding its lock from the moment they are read.
+ *
+ * A new file is written then renamed, so that other shells never read a partial file.
+ */
+fn edit_file(path: &Path, edit: impl FnOnce(&mut Vec<String>)) {
+    let Some(_lock) = lock(path) else {
+        return;
+    };
+    let mut entries = read_entries(path);
+    edit(&mut entries);
+
+    let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+    let content: String = entries.iter().map(|entry| encode(entry)).collect();
+    if write(&temporary, content).is_ok() {
+        let _ = rename(&temporary, path);
+    }
+}
+
+fn limit(variables: &Variables, name: &str, default: usize) -> usize {
+    variables.get(name).and_then(|value| value.parse().ok()).unwrap_or(default)
+}
@@ -0,0 +1,134 @@
+use crate::editor::terminal::read_byte;
+use std::io;
+
+const ESC: u8 = 0x1b;
+
+/**
+ * A key pressed by the user, decoded from the bytes sent by the terminal.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Char(char),
+    /// A letter pressed with Ctrl, in lowercase.
+    Ctrl(char),
+    /// A character pressed with Alt, or after Escape.
+    Alt(char),
+    Enter,
+    Tab,
+    Backspace,
+    /// Alt+Backspace.
+    AltBackspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    /// Ctrl+Left.
+    WordLeft,
+    /// Ctrl+Right.
+    WordRight,
+    /// An escape sequence that is not supported.
+    Unknown,
+}
+
+/**
+ * Read the next key from the terminal in raw mode.
+ *
+ * Returns `None` at the end of the input.
+ */
+pub fn read_key() -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte()? else {
+        return Ok(None);
+    };
+
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x08 | 0x7f => Key::Backspace,
+        1..=26 => Key::Ctrl((b'a' + byte - 1) as char),
+        ESC => read_escape()?,
+        0x20..=0x7e => Key::Char(byte as char),
+        0x80..=0xff => read_utf8(byte)?,
+        _ => Key::Unknown,
+    };
+
+    Ok(Some(key))
+}
+
+/**
+ * Decode what follows an Escape: a CSI sequence such as `ESC [ 1 ; 5 C`,
+ * an SS3 sequence such as `ESC O H`, or a key pressed with Alt.
+ */
+fn read_escape() -> io::Result<Key> {
+    let Some(byte) = read_byte()? else {
+        return Ok(Key::Unknown);
+    };
+
+    match byte {
+        b'[' => {
+            let mut params = String::new();
+            loop {
+                match read_byte()? {
+                    Some(c @ (b'0'..=b'9' | b';')) => params.push(c as char),
+                    Some(c) => return Ok(csi_key(&params, c)),
+                    None => return Ok(Key::Unknown),
+                }
+            }
+        }
+        b'O' => Ok(match read_byte()? {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            _ => Key::Unknown,
+        }),
+        0x08 | 0x7f => Ok(Key::AltBackspace),
+        0x20..=0x7e => Ok(Key::Alt(byte as char)),
+        _ => Ok(Key::Unknown),
+    }
+}
+
+fn csi_key(params: &str, last: u8) -> Key {
+    let ctrl = params.ends_with(";5");
+    match (params, last) {
+        (_, b'A') => Key::Up,
+        (_, b'B') => Key::Down,
+        (_, b'C') if ctrl => Key::WordRight,
+        (_, b'D') if ctrl => Key::WordLeft,
+        (_, b'C') => Key::Right,
+        (_, b'D') => Key::Left,
+        (_, b'H') | ("1" | "7", b'~') => Key::Home,
+        (_, b'F') | ("4" | "8", b'~') => Key::End,
+        ("3", b'~') => Key::Delete,
+        _ => Key::Unknown,
+    }
+}
+
+/**
+ * Read the remaining bytes of a multi-byte UTF-8 character.
+ */
+fn read_utf8(first: u8) -> io::Result<Key> {
+    let len = match first {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Ok(Key::Unknown),
+    };
+
+    let mut bytes = vec![first];
+    for _ in 1..len {
+        match read_byte()? {
+            Some(byte) => bytes.push(byte),
+            None => return Ok(Key::Unknown),
+        }
+    }
+
+    Ok(match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+        Some(c) => Key::Char(c),
+        None => Key::Unknown,
+    })
+}
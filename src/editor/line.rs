@@ -0,0 +1,468 @@
+use crate::editor::complete::{common_prefix, complete, Candidate};
+use crate::editor::keys::{read_key, Key};
+use crate::editor::terminal::{is_tty, width, RawMode, STDIN};
+use crate::shell::Shell;
+use std::io::{self, stdin, stdout, Write};
+
+/**
+ * How many killed texts are kept for `Ctrl+Y` and `Alt+Y`.
+ */
+const KILL_RING_SIZE: usize = 16;
+
+/**
+ * The characters around the parts of a prompt that take no room on the screen, written `\[` and `\]`
+ * in `PS1`. They are not printed.
+ */
+pub const START_INVISIBLE: char = '\x01';
+pub const END_INVISIBLE: char = '\x02';
+
+/**
+ * What the previous key did, so that consecutive kills are joined and `Alt+Y` follows a yank.
+ */
+#[derive(Clone, Copy, PartialEq)]
+enum LastAction {
+    Kill,
+    Yank { start: usize, len: usize },
+    Complete,
+    Other,
+}
+
+/**
+ * An interactive line editor, with Emacs-like keybindings:
+ * * `Left`/`Ctrl+B` and `Right`/`Ctrl+F` move by character.
+ * * `Ctrl+Left`/`Alt+B` and `Ctrl+Right`/`Alt+F` move by word.
+ * * `Home`/`Ctrl+A` and `End`/`Ctrl+E` move to the start and the end of the line.
+ * * `Ctrl+K`, `Ctrl+U`, `Ctrl+W`, `Alt+D` and `Alt+Backspace` kill text, which `Ctrl+Y` yanks back
+ *   and `Alt+Y` then replaces with older kills.
+ * * `Up`/`Ctrl+P` and `Down`/`Ctrl+N` go through the history, and `Ctrl+R` searches it backwards.
+ * * `Tab` completes the word before the cursor, and lists the candidates when pressed twice.
+ * * `Ctrl+L` clears the screen, `Ctrl+C` discards the line and `Ctrl+D` on an empty line ends the input.
+ */
+pub struct LineEditor {
+    kill_ring: Vec<String>,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        LineEditor { kill_ring: Vec::new() }
+    }
+
+    /**
+     * Print the prompt and read a line, without its newline.
+     *
+     * When stdin is not a terminal, the line is read as is.
+     *
+     * Returns `None` at the end of the input, and an `Interrupted` error when the line is discarded with `Ctrl+C`.
+     *
+     * # Arguments
+     * * `prompt` - The prompt, which may contain colors and several lines.
+     * * `shell` - The shell, whose history is gone through with the arrows and search,
+     *   and whose current directory and variables are used for completion.
+     */
+    pub fn read_line(&mut self, prompt: &str, shell: &Shell) -> io::Result<Option<String>> {
+        if !is_tty(STDIN) {
+            print!("{}", printable(prompt));
+            stdout().flush()?;
+
+            let mut line = String::new();
+            if stdin().read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(line.trim_end_matches('\n').to_owned()));
+        }
+
+        let _raw = RawMode::enable()?;
+        let (first_lines, last_line) = match prompt.rfind('\n') {
+            Some(newline) => prompt.split_at(newline + 1),
+            None => ("", prompt),
+        };
+        let first_lines = &printable(first_lines);
+        let mut line = Line {
+            prompt: last_line.to_owned(),
+            buffer: Vec::new(),
+            cursor: 0,
+            row: 0,
+        };
+        let entries = shell.history.entries();
+        // The position in the history, and the line being typed before going up in it.
+        let mut position = entries.len();
+        let mut draft = Vec::new();
+        let mut pending = None;
+        let mut out = stdout();
+        write!(out, "{}", first_lines)?;
+        line.refresh(&mut out)?;
+
+        let mut last = LastAction::Other;
+        loop {
+            let Some(key) = pending.take().map_or_else(read_key, |key| Ok(Some(key)))? else {
+                return Ok(None);
+            };
+
+            let mut action = LastAction::Other;
+            match key {
+                Key::Enter => {
+                    line.cursor = line.buffer.len();
+                    line.refresh(&mut out)?;
+                    writeln!(out)?;
+                    out.flush()?;
+                    return Ok(Some(line.buffer.iter().collect()));
+                }
+                Key::Ctrl('c') => {
+                    line.cursor = line.buffer.len();
+                    line.refresh(&mut out)?;
+                    writeln!(out, "^C")?;
+                    out.flush()?;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                Key::Ctrl('d') if line.buffer.is_empty() => return Ok(None),
+                Key::Ctrl('d') | Key::Delete if line.cursor < line.buffer.len() => {
+                    line.buffer.remove(line.cursor);
+                }
+                Key::Backspace if line.cursor > 0 => {
+                    line.cursor -= 1;
+                    line.buffer.remove(line.cursor);
+                }
+                Key::Left | Key::Ctrl('b') => line.cursor = line.cursor.saturating_sub(1),
+                Key::Right | Key::Ctrl('f') => line.cursor = (line.cursor + 1).min(line.buffer.len()),
+                Key::WordLeft | Key::Alt('b') => line.cursor = line.word_start(),
+                Key::WordRight | Key::Alt('f') => line.cursor = line.word_end(),
+                Key::Home | Key::Ctrl('a') => line.cursor = 0,
+                Key::End | Key::Ctrl('e') => line.cursor = line.buffer.len(),
+                Key::Ctrl('k' | 'u' | 'w') | Key::AltBackspace | Key::Alt('d') => {
+                    let (start, end) = match key {
+                        Key::Ctrl('k') => (line.cursor, line.buffer.len()),
+                        Key::Ctrl('u') => (0, line.cursor),
+                        Key::Ctrl('w') => (line.blank_word_start(), line.cursor),
+                        Key::AltBackspace => (line.word_start(), line.cursor),
+                        _ => (line.cursor, line.word_end()),
+                    };
+                    self.kill(&mut line, start, end, last == LastAction::Kill);
+                    action = LastAction::Kill;
+                }
+                Key::Ctrl('y') => {
+                    if let Some(text) = self.kill_ring.last() {
+                        action = line.insert_str(text);
+                    }
+                }
+                Key::Alt('y') => {
+                    if let LastAction::Yank { start, len } = last {
+                        if let Some(text) = self.kill_ring.pop() {
+                            self.kill_ring.insert(0, text);
+                        }
+                        line.buffer.drain(start..start + len);
+                        line.cursor = start;
+                        action = line.insert_str(self.kill_ring.last().map_or("", String::as_str));
+                    }
+                }
+                Key::Up | Key::Ctrl('p') if position > 0 => {
+                    if position == entries.len() {
+                        draft = line.buffer.clone();
+                    }
+                    position -= 1;
+                    line.set_buffer(entries[position].chars().collect());
+                }
+                Key::Down | Key::Ctrl('n') if position < entries.len() => {
+                    position += 1;
+                    match entries.get(position) {
+                        Some(entry) => line.set_buffer(entry.chars().collect()),
+                        None => line.set_buffer(std::mem::take(&mut draft)),
+                    }
+                }
+                Key::Tab => {
+                    let completion = complete(shell, &line.buffer, line.cursor);
+                    let word: String = line.buffer[completion.start..line.cursor].iter().collect();
+                    match completion.candidates.as_slice() {
+                        [] => write!(out, "\x07")?,
+                        [candidate] => {
+                            line.buffer.drain(completion.start..line.cursor);
+                            line.cursor = completion.start;
+                            line.insert_str(&candidate.text);
+                            if candidate.complete {
+                                line.insert_str(" ");
+                            }
+                        }
+                        candidates => {
+                            let prefix = common_prefix(candidates);
+                            if prefix.len() > word.len() {
+                                line.buffer.drain(completion.start..line.cursor);
+                                line.cursor = completion.start;
+                                line.insert_str(&prefix);
+                            } else if last == LastAction::Complete {
+                                list_candidates(&mut line, candidates, first_lines, &mut out)?;
+                            } else {
+                                write!(out, "\x07")?;
+                            }
+                        }
+                    }
+                    action = LastAction::Complete;
+                }
+                Key::Ctrl('r') => pending = search(&mut line, entries, &mut out)?,
+                Key::Ctrl('l') => {
+                    write!(out, "\x1b[H\x1b[2J{}", first_lines)?;
+                    line.row = 0;
+                }
+                Key::Char(c) => {
+                    line.buffer.insert(line.cursor, c);
+                    line.cursor += 1;
+                }
+                _ => {}
+            }
+
+            last = action;
+            line.refresh(&mut out)?;
+        }
+    }
+
+    /**
+     * Remove the text between `start` and `end` from the line and put it in the kill ring,
+     * joined with the previous kill when `append` is set.
+     */
+    fn kill(&mut self, line: &mut Line, start: usize, end: usize, append: bool) {
+        if start >= end {
+            return;
+        }
+
+        let text: String = line.buffer.drain(start..end).collect();
+        let forward = start == line.cursor;
+        line.cursor = start;
+
+        match self.kill_ring.last_mut() {
+            Some(last) if append && forward => last.push_str(&text),
+            Some(last) if append => last.insert_str(0, &text),
+            _ => {
+                if self.kill_ring.len() == KILL_RING_SIZE {
+                    self.kill_ring.remove(0);
+                }
+                self.kill_ring.push(text);
+            }
+        }
+    }
+}
+
+/**
+ * Search the history backwards for the lines containing what the user types, as with `Ctrl+R` in bash.
+ *
+ * `Ctrl+R` goes to the next older match and `Ctrl+G` or `Ctrl+C` cancels the search.
+ * Any other key puts the match in the line, and is returned to be handled as usual.
+ */
+fn search(line: &mut Line, entries: &[String], out: &mut impl Write) -> io::Result<Option<Key>> {
+    let prompt = std::mem::take(&mut line.prompt);
+    let original = (line.buffer.clone(), line.cursor);
+    let mut query = String::new();
+    let mut found: Option<usize> = None;
+    let mut failed = false;
+
+    let key = loop {
+        line.prompt = format!("({}reverse-i-search)`{}': ", if failed { "failed " } else { "" }, query);
+        line.refresh(out)?;
+
+        let Some(key) = read_key()? else {
+            break None;
+        };
+        let from = match key {
+            Key::Char(c) => {
+                query.push(c);
+                found.map_or(entries.len(), |index| index + 1)
+            }
+            Key::Backspace => {
+                query.pop();
+                entries.len()
+            }
+            Key::Ctrl('r') => found.unwrap_or(entries.len()),
+            Key::Ctrl('g' | 'c') => {
+                (line.buffer, line.cursor) = original;
+                break Some(Key::Unknown);
+            }
+            key => break Some(key),
+        };
+
+        match entries[..from].iter().rposition(|entry| entry.contains(&query)) {
+            Some(index) => {
+                let entry = &entries[index];
+                line.buffer = entry.chars().collect();
+                line.cursor = entry[..entry.find(&query).unwrap_or(0)].chars().count();
+                found = Some(index);
+                failed = false;
+            }
+            None => failed = true,
+        }
+    };
+
+    line.prompt = prompt;
+    Ok(key)
+}
+
+/**
+ * Print the completion candidates in columns below the line, sorted down the columns as in bash,
+ * then print the prompt again.
+ */
+fn list_candidates(line: &mut Line, candidates: &[Candidate], first_lines: &str, out: &mut impl Write) -> io::Result<()> {
+    let cursor = line.cursor;
+    line.cursor = line.buffer.len();
+    line.refresh(out)?;
+
+    let column_width = candidates.iter().map(|candidate| candidate.display.chars().count()).max().unwrap_or(0) + 2;
+    let columns = (width() / column_width).max(1);
+    let rows = candidates.len().div_ceil(columns);
+    let mut listing = String::from("\n");
+    for row in 0..rows {
+        for candidate in candidates.iter().skip(row).step_by(rows) {
+            listing.push_str(&format!("{:<width$}", candidate.display, width = column_width));
+        }
+        listing.truncate(listing.trim_end().len());
+        listing.push('\n');
+    }
+    write!(out, "{}{}", listing, first_lines)?;
+
+    line.cursor = cursor;
+    line.row = 0;
+    Ok(())
+}
+
+/**
+ * The line being edited and its state on the screen.
+ */
+struct Line {
+    /// The last line of the prompt, which is redrawn with the buffer.
+    prompt: String,
+    buffer: Vec<char>,
+    cursor: usize,
+    /// The screen row of the cursor, relative to the first row of the prompt.
+    row: usize,
+}
+
+impl Line {
+    /**
+     * Replace the whole buffer, moving the cursor to its end.
+     */
+    fn set_buffer(&mut self, buffer: Vec<char>) {
+        self.cursor = buffer.len();
+        self.buffer = buffer;
+    }
+
+    fn insert_str(&mut self, text: &str) -> LastAction {
+        let start = self.cursor;
+        let len = text.chars().count();
+        for (i, c) in text.chars().enumerate() {
+            self.buffer.insert(start + i, c);
+        }
+        self.cursor += len;
+        LastAction::Yank { start, len }
+    }
+
+    /**
+     * Find the start of the word before the cursor, words being made of letters and digits.
+     */
+    fn word_start(&self) -> usize {
+        let mut i = self.cursor;
+        while i > 0 && !self.buffer[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        while i > 0 && self.buffer[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        i
+    }
+
+    /**
+     * Find the end of the word after the cursor, words being made of letters and digits.
+     */
+    fn word_end(&self) -> usize {
+        let mut i = self.cursor;
+        while i < self.buffer.len() && !self.buffer[i].is_alphanumeric() {
+            i += 1;
+        }
+        while i < self.buffer.len() && self.buffer[i].is_alphanumeric() {
+            i += 1;
+        }
+        i
+    }
+
+    /**
+     * Find the start of the word before the cursor, words being separated by blanks.
+     */
+    fn blank_word_start(&self) -> usize {
+        let mut i = self.cursor;
+        while i > 0 && self.buffer[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !self.buffer[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+
+    /**
+     * Redraw the prompt and the buffer, then put the cursor back in place.
+     *
+     * The line may wrap over several rows of the terminal, so the redraw starts from
+     * the row of the prompt and clears everything below it.
+     */
+    fn refresh(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let width = width();
+        let prompt_width = visible_width(&self.prompt);
+        let end = prompt_width + self.buffer.len();
+        let cursor = prompt_width + self.cursor;
+
+        let mut screen = String::new();
+        if self.row > 0 {
+            screen.push_str(&format!("\x1b[{}A", self.row));
+        }
+        screen.push_str("\r\x1b[J");
+        screen.push_str(&printable(&self.prompt));
+        screen.extend(&self.buffer);
+        // Past the last column the terminal waits before wrapping, so move to the next row ourselves.
+        if end > 0 && end.is_multiple_of(width) {
+            screen.push_str("\r\n");
+        }
+
+        let (end_row, cursor_row, cursor_col) = (end / width, cursor / width, cursor % width);
+        if end_row > cursor_row {
+            screen.push_str(&format!("\x1b[{}A", end_row - cursor_row));
+        }
+        screen.push('\r');
+        if cursor_col > 0 {
+            screen.push_str(&format!("\x1b[{}C", cursor_col));
+        }
+        self.row = cursor_row;
+
+        out.write_all(screen.as_bytes())?;
+        out.flush()
+    }
+}
+
+/**
+ * Remove the markers of the invisible parts of a prompt, before printing it.
+ */
+fn printable(prompt: &str) -> String {
+    prompt.replace([START_INVISIBLE, END_INVISIBLE], "")
+}
+
+/**
+ * Get the number of columns a text takes on the screen, ignoring its color escape sequences
+ * and the parts marked as invisible.
+ */
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == START_INVISIBLE {
+            for c in chars.by_ref() {
+                if c == END_INVISIBLE {
+                    break;
+                }
+            }
+        } else if c == END_INVISIBLE {
+            continue;
+        } else if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
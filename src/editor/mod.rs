@@ -0,0 +1,7 @@
+pub(crate) mod complete;
+pub(crate) mod git;
+pub(crate) mod history;
+pub(crate) mod keys;
+pub(crate) mod line;
+pub(crate) mod prompt;
+pub(crate) mod terminal;
\ No newline at end of file
@@ -0,0 +1,187 @@
+use crate::editor::git::GitPrompt;
+use crate::editor::line::{END_INVISIBLE, START_INVISIBLE};
+use crate::shell::{Shell, SHELL_NAME};
+use crate::utils::date::{local_tm, month_to_str, weekday_to_str};
+use crate::utils::utils::extrac_guid_from_file;
+use std::os::raw::{c_char, c_int, c_uint};
+use std::path::Path;
+use std::time::SystemTime;
+
+/**
+ * The prompt used when `PS1` is not set: the abbreviated current directory on a grey background,
+ * then the git branch.
+ */
+pub const DEFAULT_PS1: &str = "\\[\\e[1;33;48;5;238m\\]\\w\\[\\e[0m\\e[38;5;245m\\]\\g\\[\\e[0m\\] \\$ ";
+
+/**
+ * The prompt of continuation lines, used when `PS2` is not set.
+ */
+pub const DEFAULT_PS2: &str = "> ";
+
+extern "C" {
+    fn geteuid() -> c_uint;
+    fn gethostname(name: *mut c_char, len: usize) -> c_int;
+}
+
+/**
+ * Build a prompt from the value of `PS1` or `PS2`, replacing its escapes:
+ * * `\u` the user name, `\h` the host name up to the first dot and `\H` the full host name,
+ * * `\w` the current directory with the home directory shortened to `~`, `\W` its last component
+ *   and `\P` the full path,
+ * * `\t` the time as `HH:MM:SS`, `\T` in 12-hour format, `\@` as `HH:MM am`, `\A` as `HH:MM`
+ *   and `\d` the date as `Tue May 26`,
+ * * `\g` the git segment, like ` (main *)`, or nothing outside git repositories,
+ * * `\?` the status of the last command, `\j` the number of jobs, always 0 since commands are
+ *   never run in the background, `\s` the name of the shell and `\$` a `#` for root or a `$` otherwise,
+ * * `\e` an escape character and `\nnn` the character with the octal code `nnn`, to write colors,
+ * * `\n` a newline, `\a` a bell and `\\` a backslash.
+ *
+ * `\[` and `\]` surround the characters that take no room on the screen, as in bash,
+ * so that the line editor leaves them out of the width of the prompt.
+ *
+ * # Arguments
+ * * `format` - The value of the prompt variable.
+ * * `shell` - The shell, for its current directory, variables and last status.
+ * * `git` - The git segment, with its cache.
+ *
+ * # Example
+ * ```rust
+ * use shell::editor::git::GitPrompt;
+ * use shell::editor::prompt::render_prompt;
+ * use shell::shell::Shell;
+ *
+ * let shell = Shell::new();
+ * let prompt = render_prompt("\\u@\\h:\\w\\g\\$ ", &shell, &mut GitPrompt::default());
+ * ```
+ */
+pub fn render_prompt(format: &str, shell: &Shell, git: &mut GitPrompt) -> String {
+    let mut prompt = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            prompt.push(c);
+            continue;
+        }
+
+        let Some(escape) = chars.next() else {
+            prompt.push('\\');
+            break;
+        };
+        match escape {
+            'u' => prompt.push_str(&user_name(shell)),
+            'h' => prompt.push_str(host_name().split('.').next().unwrap_or_default()),
+            'H' => prompt.push_str(&host_name()),
+            'w' => prompt.push_str(&abbreviate_home(shell.current_dir(), shell.variables.get("HOME"))),
+            'W' => prompt.push_str(&base_name(shell.current_dir(), shell.variables.get("HOME"))),
+            'P' => prompt.push_str(&shell.current_dir().to_string_lossy()),
+            'g' => prompt.push_str(&git.segment(shell.current_dir())),
+            't' | 'T' | '@' | 'A' | 'd' => prompt.push_str(&time(escape)),
+            '?' => prompt.push_str(&shell.last_status.to_string()),
+            // There is no job control, so there are never any jobs.
+            'j' => prompt.push('0'),
+            's' => prompt.push_str(SHELL_NAME),
+            '$' => prompt.push(if unsafe { geteuid() } == 0 { '#' } else { '$' }),
+            'e' => prompt.push('\x1b'),
+            'n' => prompt.push('\n'),
+            'a' => prompt.push('\x07'),
+            '\\' => prompt.push('\\'),
+            '[' => prompt.push(START_INVISIBLE),
+            ']' => prompt.push(END_INVISIBLE),
+            '0'..='7' => {
+                let mut code = escape.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                prompt.extend(char::from_u32(code));
+            }
+            other => {
+                prompt.push('\\');
+                prompt.push(other);
+            }
+        }
+    }
+
+    prompt
+}
+
+/**
+ * Replace the home directory at the start of a path with `~`.
+ *
+ * Paths are compared by components, so `/home/user2` is not shortened when the home directory
+ * is `/home/user`.
+ *
+ * # Arguments
+ * * `path` - The path to shorten.
+ * * `home` - The home directory, if known.
+ *
+ * # Example
+ * ```rust
+ * use std::path::Path;
+ * use shell::editor::prompt::abbreviate_home;
+ *
+ * assert_eq!(abbreviate_home(Path::new("/home/user/src"), Some("/home/user")), "~/src");
+ * assert_eq!(abbreviate_home(Path::new("/home/user2"), Some("/home/user")), "/home/user2");
+ * ```
+ */
+pub fn abbreviate_home(path: &Path, home: Option<&str>) -> String {
+    let home = home.map(Path::new).filter(|home| home.parent().is_some());
+    match home.and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_owned(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+/**
+ * Get the last component of a path, or `~` for the home directory as in bash.
+ */
+fn base_name(path: &Path, home: Option<&str>) -> String {
+    if home.is_some_and(|home| Path::new(home) == path) {
+        return "~".to_owned();
+    }
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+/**
+ * Get the name of the user, from `USER` or else from the user database.
+ */
+fn user_name(shell: &Shell) -> String {
+    match shell.variables.get("USER") {
+        Some(user) if !user.is_empty() => user.to_owned(),
+        _ => extrac_guid_from_file(unsafe { geteuid() }, "/etc/passwd"),
+    }
+}
+
+fn host_name() -> String {
+    let mut buffer = [0u8; 256];
+    if unsafe { gethostname(buffer.as_mut_ptr() as *mut c_char, buffer.len()) } != 0 {
+        return String::new();
+    }
+    let len = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+/**
+ * Format the current local time for the `\t`, `\T`, `\@`, `\A` and `\d` escapes.
+ */
+fn time(escape: char) -> String {
+    let tm = local_tm(SystemTime::now());
+    let hour_12 = if tm.tm_hour % 12 == 0 { 12 } else { tm.tm_hour % 12 };
+    match escape {
+        't' => format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec),
+        'T' => format!("{:02}:{:02}:{:02}", hour_12, tm.tm_min, tm.tm_sec),
+        '@' => format!("{:02}:{:02} {}", hour_12, tm.tm_min, if tm.tm_hour < 12 { "am" } else { "pm" }),
+        'A' => format!("{:02}:{:02}", tm.tm_hour, tm.tm_min),
+        _ => format!("{} {} {:02}", weekday_to_str(tm.tm_wday), month_to_str(tm.tm_mon), tm.tm_mday),
+    }
+}
@@ -0,0 +1,152 @@
+use std::io;
+use std::os::raw::{c_int, c_uint, c_ulong, c_void};
+
+pub const STDIN: c_int = 0;
+pub const STDOUT: c_int = 1;
+
+const NCCS: usize = 32;
+const TCSADRAIN: c_int = 1;
+const TIOCGWINSZ: c_ulong = 0x5413;
+
+const ICRNL: c_uint = 0o400;
+const IXON: c_uint = 0o2000;
+const ISIG: c_uint = 0o1;
+const ICANON: c_uint = 0o2;
+const ECHO: c_uint = 0o10;
+const IEXTEN: c_uint = 0o100000;
+const VTIME: usize = 5;
+const VMIN: usize = 6;
+
+/**
+ * The terminal attributes of `termios.h`, as laid out on Linux.
+ */
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Termios {
+    c_iflag: c_uint,
+    c_oflag: c_uint,
+    c_cflag: c_uint,
+    c_lflag: c_uint,
+    c_line: u8,
+    c_cc: [u8; NCCS],
+    c_ispeed: c_uint,
+    c_ospeed: c_uint,
+}
+
+#[repr(C)]
+struct Winsize {
+    ws_row: u16,
+    ws_col: u16,
+    ws_xpixel: u16,
+    ws_ypixel: u16,
+}
+
+extern "C" {
+    fn isatty(fd: c_int) -> c_int;
+    fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+    fn tcsetattr(fd: c_int, optional_actions: c_int, termios: *const Termios) -> c_int;
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+}
+
+/**
+ * Check whether a file descriptor is a terminal.
+ */
+pub fn is_tty(fd: c_int) -> bool {
+    unsafe { isatty(fd) == 1 }
+}
+
+/**
+ * Get the number of columns of the terminal, 80 if it cannot be known.
+ */
+pub fn width() -> usize {
+    let mut size = Winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    match unsafe { ioctl(STDOUT, TIOCGWINSZ, &mut size as *mut Winsize) } {
+        0 if size.ws_col > 0 => size.ws_col as usize,
+        _ => 80,
+    }
+}
+
+/**
+ * Read a single byte from stdin, without any buffering.
+ *
+ * Returns `None` at the end of the input.
+ */
+pub fn read_byte() -> io::Result<Option<u8>> {
+    let mut byte = 0u8;
+    loop {
+        match unsafe { read(STDIN, &mut byte as *mut u8 as *mut c_void, 1) } {
+            1 => return Ok(Some(byte)),
+            0 => return Ok(None),
+            _ => {
+                let error = io::Error::last_os_error();
+                if error.kind() != io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Read a line from stdin one byte at a time, without its newline, so that the programs run
+ * afterwards can still read the rest of the input.
+ *
+ * Returns `None` at the end of the input.
+ */
+pub fn read_raw_line() -> io::Result<Option<String>> {
+    let mut bytes = Vec::new();
+    loop {
+        match read_byte()? {
+            Some(b'\n') => break,
+            Some(byte) => bytes.push(byte),
+            None if bytes.is_empty() => return Ok(None),
+            None => break,
+        }
+    }
+
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/**
+ * Puts the terminal in raw mode while it lives, so that keys are read one by one without echo,
+ * and restores the previous mode when dropped.
+ *
+ * Output processing is kept, so `\n` still moves to the start of the next line.
+ */
+pub struct RawMode {
+    original: Termios,
+}
+
+impl RawMode {
+    pub fn enable() -> io::Result<RawMode> {
+        let mut original = unsafe { std::mem::zeroed::<Termios>() };
+        if unsafe { tcgetattr(STDIN, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut raw = original;
+        raw.c_iflag &= !(ICRNL | IXON);
+        raw.c_lflag &= !(ECHO | ICANON | ISIG | IEXTEN);
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+        if unsafe { tcsetattr(STDIN, TCSADRAIN, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(RawMode { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            tcsetattr(STDIN, TCSADRAIN, &self.original);
+        }
+    }
+}
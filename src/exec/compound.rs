@@ -0,0 +1,166 @@
+use crate::exec::list::{check_interrupt, run_list};
+use crate::exec::stream::Streams;
+use crate::expansion::arithmetic::evaluate;
+use crate::expansion::expand::{expand_pattern, expand_word, expand_words};
+use crate::expansion::pattern::matches;
+use crate::parser::ast::{CaseItem, CommandList, CompoundCommand};
+use crate::parser::lexer::Word;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+
+/**
+ * Run a compound command in the shell itself.
+ *
+ * The status of a compound command is the one of the last command of its body that ran,
+ * or 0 when none did, as for an `if` whose conditions all failed or a loop over no words.
+ *
+ * # Arguments
+ * * `shell` - The shell running the command.
+ * * `command` - The command to run.
+ * * `streams` - The streams of the command.
+ */
+pub fn run_compound(shell: &mut Shell, command: &CompoundCommand, streams: &Streams) -> Result<i32, ShellError> {
+    match command {
+        CompoundCommand::Group(body) => run_list(shell, body, streams),
+        CompoundCommand::If { branches, otherwise } => {
+            for (condition, body) in branches {
+                if run_list(shell, condition, streams)? == 0 {
+                    return run_list(shell, body, streams);
+                }
+            }
+            match otherwise {
+                Some(body) => run_list(shell, body, streams),
+                None => Ok(0),
+            }
+        }
+        CompoundCommand::While { condition, body, until } => {
+            run_loop(shell, |shell| run_while(shell, condition, body, *until, streams))
+        }
+        CompoundCommand::For { name, words, body } => run_loop(shell, |shell| {
+            let values = match words {
+                Some(words) => expand_words(shell, words)?,
+                None => shell.positional.clone(),
+            };
+            run_for(shell, name, &values, body, streams)
+        }),
+        CompoundCommand::ArithmeticFor { init, condition, step, body } => {
+            run_loop(shell, |shell| run_arithmetic_for(shell, [init, condition, step], body, streams))
+        }
+        CompoundCommand::Case { word, items } => run_case(shell, word, items, streams),
+    }
+}
+
+/**
+ * Run a loop, counting it in the loops that `break` and `continue` can leave.
+ */
+fn run_loop(shell: &mut Shell, run: impl FnOnce(&mut Shell) -> Result<i32, ShellError>) -> Result<i32, ShellError> {
+    shell.loops += 1;
+    let result = run(shell);
+    shell.loops -= 1;
+    result
+}
+
+/**
+ * Run a part of a loop, handling the `break` and `continue` aimed at it.
+ *
+ * Returns the status of the list and whether the loop should stop. `break n` and `continue n`
+ * with `n` above 1 are passed up to the enclosing loop as `break n-1` and `continue n-1`.
+ * Ctrl+C stops the loop, even when its commands ignore it.
+ */
+fn run_iteration(shell: &mut Shell, list: &CommandList, streams: &Streams) -> Result<(i32, bool), ShellError> {
+    check_interrupt()?;
+    match run_list(shell, list, streams) {
+        Ok(status) => Ok((status, false)),
+        Err(ShellError::Break(1)) => Ok((0, true)),
+        Err(ShellError::Continue(1)) => Ok((0, false)),
+        Err(ShellError::Break(count)) => Err(ShellError::Break(count - 1)),
+        Err(ShellError::Continue(count)) => Err(ShellError::Continue(count - 1)),
+        Err(e) => Err(e),
+    }
+}
+
+fn run_while(
+    shell: &mut Shell,
+    condition: &CommandList,
+    body: &CommandList,
+    until: bool,
+    streams: &Streams,
+) -> Result<i32, ShellError> {
+    let mut status = 0;
+    loop {
+        let (condition_status, stop) = run_iteration(shell, condition, streams)?;
+        if stop || (condition_status == 0) == until {
+            return Ok(status);
+        }
+
+        let (body_status, stop) = run_iteration(shell, body, streams)?;
+        status = body_status;
+        if stop {
+            return Ok(status);
+        }
+    }
+}
+
+fn run_for(shell: &mut Shell, name: &str, values: &[String], body: &CommandList, streams: &Streams) -> Result<i32, ShellError> {
+    let mut status = 0;
+    for value in values {
+        shell.variables.set(name, value);
+        let (body_status, stop) = run_iteration(shell, body, streams)?;
+        status = body_status;
+        if stop {
+            break;
+        }
+    }
+
+    Ok(status)
+}
+
+/**
+ * Run a `for ((init; condition; step))` loop. The expressions are expanded again before every evaluation.
+ */
+fn run_arithmetic_for(
+    shell: &mut Shell,
+    [init, condition, step]: [&Word; 3],
+    body: &CommandList,
+    streams: &Streams,
+) -> Result<i32, ShellError> {
+    let mut status = 0;
+    evaluate_word(shell, init)?;
+    while evaluate_word(shell, condition)?.unwrap_or(1) != 0 {
+        let (body_status, stop) = run_iteration(shell, body, streams)?;
+        status = body_status;
+        if stop {
+            break;
+        }
+        evaluate_word(shell, step)?;
+    }
+
+    Ok(status)
+}
+
+/**
+ * Expand and evaluate an arithmetic expression, returning `None` for an empty one.
+ */
+fn evaluate_word(shell: &mut Shell, word: &Word) -> Result<Option<i64>, ShellError> {
+    let expression = expand_word(shell, word)?;
+    if expression.trim().is_empty() {
+        return Ok(None);
+    }
+    evaluate(&mut shell.variables, &expression).map(Some)
+}
+
+/**
+ * Run the body of the first item of a `case` command with a pattern matching the word.
+ */
+fn run_case(shell: &mut Shell, word: &Word, items: &[CaseItem], streams: &Streams) -> Result<i32, ShellError> {
+    let value = expand_word(shell, word)?;
+    for item in items {
+        for pattern in &item.patterns {
+            if matches(&expand_pattern(shell, pattern)?, &value) {
+                return run_list(shell, &item.body, streams);
+            }
+        }
+    }
+
+    Ok(0)
+}
@@ -0,0 +1,120 @@
+use crate::exec::stream::Streams;
+use crate::utils::error::ShellError;
+use crate::utils::messages::PERMISSION_DENIED;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+/**
+ * Find an executable by looking through the directories of a `PATH` value.
+ *
+ * # Arguments
+ * * `name` - The name of the program.
+ * * `path` - The value of the `PATH` variable.
+ *
+ * # Example
+ * ```rust
+ * use shell::exec::external::find_in_path;
+ *
+ * let git = find_in_path("git", "/usr/local/bin:/usr/bin:/bin");
+ * ```
+ */
+pub fn find_in_path(name: &str, path: &str) -> Option<PathBuf> {
+    env::split_paths(path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+/**
+ * Check whether a path points to a regular file with at least one execute bit set.
+ */
+pub fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/**
+ * Resolve the program to run.
+ *
+ * Names containing a slash are taken as paths relative to the current directory,
+ * other names are searched in the given `PATH` value.
+ */
+pub fn resolve_program(current_dir: &Path, name: &str, path: &str) -> Result<PathBuf, ShellError> {
+    if !name.contains('/') {
+        return find_in_path(name, path).ok_or_else(|| ShellError::CommandNotFound(name.to_owned()));
+    }
+
+    let path = current_dir.join(name);
+    if !path.exists() {
+        return Err(ShellError::CommandNotFound(name.to_owned()));
+    }
+    if !is_executable(&path) {
+        return Err(ShellError::NotExecutable(format!("{}: {PERMISSION_DENIED}", name)));
+    }
+
+    Ok(path)
+}
+
+/**
+ * Convert the exit status of a child into a shell status code.
+ *
+ * Programs killed by a signal get `128 + signal`, like in other shells.
+ */
+pub fn status_code(status: ExitStatus) -> i32 {
+    match status.code() {
+        Some(code) => code,
+        None => 128 + status.signal().unwrap_or(0),
+    }
+}
+
+/**
+ * Run an external program and wait for it to finish.
+ *
+ * The child runs in the current directory with exactly the given environment,
+ * and is connected to the given streams.
+ *
+ * # Arguments
+ * * `current_dir` - The current directory.
+ * * `name` - The name or path of the program.
+ * * `args` - The arguments passed to the program.
+ * * `env` - The environment of the program, also used to look up `PATH`.
+ * * `streams` - The standard streams of the program.
+ *
+ * # Example
+ * ```rust
+ * use std::path::Path;
+ * use shell::exec::external::run_external;
+ * use shell::exec::stream::Streams;
+ *
+ * let current_dir = Path::new("/tmp");
+ * let env = vec![("PATH".to_owned(), "/usr/bin:/bin".to_owned())];
+ * let status = run_external(current_dir, "true", &[], &env, &Streams::inherit());
+ * assert_eq!(status.unwrap(), 0);
+ * ```
+ */
+pub fn run_external(current_dir: &Path, name: &str, args: &[&str], env: &[(String, String)], streams: &Streams) -> Result<i32, ShellError> {
+    let path = env.iter().find(|(key, _)| key == "PATH").map(|(_, value)| value.as_str()).unwrap_or("");
+    let program = resolve_program(current_dir, name, path)?;
+    let streams = streams.try_clone()?;
+
+    let status = Command::new(&program)
+        .arg0(name)
+        .args(args)
+        .current_dir(current_dir)
+        .env_clear()
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(streams.stdin.into_stdio())
+        .stdout(streams.stdout.into_stdio())
+        .stderr(streams.stderr.into_stdio())
+        .status()
+        .map_err(|e| match e.kind() {
+            ErrorKind::PermissionDenied => ShellError::NotExecutable(format!("{}: {PERMISSION_DENIED}", name)),
+            _ => ShellError::IoError(Error::new(e.kind(), format!("{}: {}", name, e))),
+        })?;
+
+    Ok(status_code(status))
+}
@@ -0,0 +1,58 @@
+use crate::exec::pipeline::run_single;
+use crate::exec::stream::Streams;
+use crate::parser::ast::Command;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::messages::MAX_NESTING_EXCEEDED;
+use std::io;
+
+/**
+ * The number of functions that can run inside each other, like `FUNCNEST` in bash,
+ * so that a function calling itself forever fails instead of overflowing the stack.
+ */
+pub const MAX_FUNCTION_DEPTH: usize = 200;
+
+/**
+ * Call a function, its arguments becoming the positional parameters `$1`, `$2`, ...
+ *
+ * The positional parameters of the caller and the variables declared with `local` in the function
+ * are put back once the function is done. The call fails when `MAX_FUNCTION_DEPTH` functions
+ * are already running.
+ *
+ * Returns the status given to `return`, or else the status of the last command of the function.
+ *
+ * # Arguments
+ * * `shell` - The shell running the function.
+ * * `name` - The name of the function.
+ * * `body` - The body of the function.
+ * * `args` - The arguments passed to the function.
+ * * `streams` - The streams of the call.
+ */
+pub fn call_function(
+    shell: &mut Shell,
+    name: &str,
+    body: &Command,
+    args: &[String],
+    streams: &Streams,
+) -> Result<i32, ShellError> {
+    // Every function being run has its frame of `local` variables.
+    if shell.locals.len() >= MAX_FUNCTION_DEPTH {
+        let message = format!("{}: {MAX_NESTING_EXCEEDED} ({MAX_FUNCTION_DEPTH})", name);
+        return Err(ShellError::IoError(io::Error::other(message)));
+    }
+
+    let positional = std::mem::replace(&mut shell.positional, args.to_vec());
+    shell.locals.push(Vec::new());
+
+    let result = run_single(shell, body, streams);
+
+    for (name, var) in shell.locals.pop().unwrap_or_default().into_iter().rev() {
+        shell.variables.restore(&name, var);
+    }
+    shell.positional = positional;
+
+    match result {
+        Err(ShellError::Return(status)) => Ok(status),
+        result => result,
+    }
+}
@@ -0,0 +1,64 @@
+use crate::exec::pipeline::run_pipeline;
+use crate::exec::stream::Streams;
+use crate::parser::ast::{AndOrList, CommandList, Connector};
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::signal::interrupted;
+
+/**
+ * Run the and-or lists of a command list one after the other.
+ *
+ * Returns the status of the last pipeline that ran.
+ *
+ * # Arguments
+ * * `shell` - The shell running the list.
+ * * `list` - The list to run.
+ * * `streams` - The streams of the whole list.
+ */
+pub fn run_list(shell: &mut Shell, list: &CommandList, streams: &Streams) -> Result<i32, ShellError> {
+    let mut status = 0;
+    for and_or in &list.items {
+        status = run_and_or(shell, and_or, streams)?;
+    }
+
+    Ok(status)
+}
+
+/**
+ * Run an and-or list: `&&` skips the next pipeline on failure and `||` on success.
+ *
+ * The status of every pipeline is recorded in the shell as `$?`. The list stops with
+ * `ShellError::Interrupted` once Ctrl+C was pressed.
+ */
+pub fn run_and_or(shell: &mut Shell, and_or: &AndOrList, streams: &Streams) -> Result<i32, ShellError> {
+    let mut status = run_pipeline(shell, &and_or.first, streams)?;
+    shell.last_status = status;
+    check_interrupt()?;
+
+    for (connector, pipeline) in &and_or.rest {
+        let run = match connector {
+            Connector::And => status == 0,
+            Connector::Or => status != 0,
+        };
+        if run {
+            status = run_pipeline(shell, pipeline, streams)?;
+            shell.last_status = status;
+            check_interrupt()?;
+        }
+    }
+
+    Ok(status)
+}
+
+/**
+ * Fail with `ShellError::Interrupted` when Ctrl+C was pressed.
+ *
+ * A program killed by Ctrl+C is in the foreground process group with the shell, which gets
+ * the signal too, so this also covers the commands that stopped with status 130 because of it.
+ */
+pub fn check_interrupt() -> Result<(), ShellError> {
+    if interrupted() {
+        return Err(ShellError::Interrupted);
+    }
+    Ok(())
+}
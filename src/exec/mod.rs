@@ -0,0 +1,8 @@
+pub(crate) mod compound;
+pub(crate) mod external;
+pub(crate) mod function;
+pub(crate) mod list;
+pub(crate) mod pipeline;
+pub(crate) mod redirect;
+pub(crate) mod script;
+pub(crate) mod stream;
@@ -0,0 +1,194 @@
+use crate::exec::compound::run_compound;
+use crate::exec::redirect::apply_redirects;
+use crate::exec::stream::{Input, Output, Streams};
+use crate::expansion::expand::{expand_assignment, expand_words};
+use crate::parser::ast::{Command, Pipeline, SimpleCommand};
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::variables::Variable;
+use std::io::{pipe, Write};
+use std::sync::Arc;
+use std::thread;
+
+/**
+ * The stack size of the threads running the commands of a pipeline, the one of the main thread
+ * on Linux, so that functions can run `MAX_FUNCTION_DEPTH` deep in a pipeline too.
+ */
+const STAGE_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/**
+ * Run a pipeline and return its exit status.
+ *
+ * A pipeline with a single command runs in the shell itself, so that builtins like `cd`
+ * affect it. Otherwise every command runs in its own thread with a copy of the shell,
+ * connected to its neighbours with pipes.
+ *
+ * The status is the one of the last command, or with the `pipefail` option
+ * the one of the last command that failed.
+ *
+ * # Arguments
+ * * `shell` - The shell running the pipeline.
+ * * `pipeline` - The pipeline to run.
+ * * `streams` - The streams of the whole pipeline.
+ */
+pub fn run_pipeline(shell: &mut Shell, pipeline: &Pipeline, streams: &Streams) -> Result<i32, ShellError> {
+    let commands = &pipeline.commands;
+    if commands.is_empty() {
+        return Ok(0);
+    }
+
+    if commands.len() == 1 {
+        return run_single(shell, &commands[0], streams);
+    }
+
+    let mut stages = Vec::with_capacity(commands.len());
+    let mut stdin = streams.stdin.try_clone()?;
+    for (i, command) in commands.iter().enumerate() {
+        let (stdout, next_stdin) = if i + 1 == commands.len() {
+            (streams.stdout.try_clone()?, None)
+        } else {
+            let (reader, writer) = pipe()?;
+            (Output::Pipe(writer), Some(Input::Pipe(reader)))
+        };
+
+        let stage_streams = Streams {
+            stdin,
+            stdout,
+            stderr: streams.stderr.try_clone()?,
+        };
+        stages.push((command, stage_streams));
+
+        stdin = next_stdin.unwrap_or(Input::Stdin);
+    }
+
+    let statuses = thread::scope(|scope| {
+        let handles = stages
+            .into_iter()
+            .map(|(command, stage_streams)| {
+                let mut stage_shell = shell.clone();
+                thread::Builder::new().stack_size(STAGE_STACK_SIZE).spawn_scoped(scope, move || {
+                    run_stage(&mut stage_shell, command, stage_streams).unwrap_or_else(|e| e.status())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok::<Vec<i32>, ShellError>(handles.into_iter().map(|handle| handle.join().unwrap_or(1)).collect())
+    })?;
+
+    let last = *statuses.last().unwrap_or(&0);
+    if shell.options.pipefail {
+        Ok(statuses.into_iter().rev().find(|&status| status != 0).unwrap_or(0))
+    } else {
+        Ok(last)
+    }
+}
+
+/**
+ * Run a single command in the shell itself, such as a pipeline of one command or the body of a function.
+ *
+ * # Arguments
+ * * `shell` - The shell running the command.
+ * * `command` - The command to run.
+ * * `streams` - The streams of the command.
+ */
+pub fn run_single(shell: &mut Shell, command: &Command, streams: &Streams) -> Result<i32, ShellError> {
+    run_stage(shell, command, streams.try_clone()?)
+}
+
+/**
+ * Set the variables of a command made only of assignments, such as `NAME=value`.
+ */
+fn assign(shell: &mut Shell, command: &SimpleCommand) -> Result<(), ShellError> {
+    for assignment in &command.assignments {
+        let value = expand_assignment(shell, &assignment.value)?;
+        shell.variables.set(&assignment.name, &value);
+    }
+
+    Ok(())
+}
+
+/**
+ * Export the assignments written before a command, such as `FOO=1 cmd`, for the duration of the command.
+ *
+ * Returns the previous state of the variables, to restore once the command is done.
+ */
+fn assign_temporarily(shell: &mut Shell, command: &SimpleCommand) -> Result<Vec<(String, Option<Variable>)>, ShellError> {
+    let values = command
+        .assignments
+        .iter()
+        .map(|assignment| expand_assignment(shell, &assignment.value))
+        .collect::<Result<Vec<String>, ShellError>>()?;
+
+    let mut saved = Vec::with_capacity(values.len());
+    for (assignment, value) in command.assignments.iter().zip(values) {
+        saved.push((assignment.name.clone(), shell.variables.get_variable(&assignment.name)));
+        shell.variables.export(&assignment.name, Some(&value));
+    }
+
+    Ok(saved)
+}
+
+fn has_substitution(command: &SimpleCommand) -> bool {
+    command.words.iter().any(|word| word.has_substitution())
+        || command.assignments.iter().any(|assignment| assignment.value.has_substitution())
+}
+
+/**
+ * Run a command of a pipeline.
+ *
+ * Errors are reported on the stderr of the command, after the location of the command when it
+ * comes from a file, and turned into its status, except for `exit`, `return`, `break`, `continue`
+ * and Ctrl+C which are passed up to the caller.
+ */
+fn run_stage(shell: &mut Shell, command: &Command, mut streams: Streams) -> Result<i32, ShellError> {
+    let result = match command {
+        Command::Simple(command) => run_simple(shell, command, &mut streams),
+        Command::Compound { body, redirects } => {
+            apply_redirects(shell, redirects, &mut streams).and_then(|_| run_compound(shell, body, &streams))
+        }
+        Command::Function { name, body } => {
+            shell.functions.insert(name.clone(), Arc::clone(body));
+            Ok(0)
+        }
+    };
+    let _ = streams.stdout.flush();
+
+    match result {
+        Ok(status) => Ok(status),
+        Err(e @ (ShellError::Exit(_) | ShellError::Return(_) | ShellError::Break(_) | ShellError::Continue(_) | ShellError::Interrupted)) => {
+            Err(e)
+        }
+        Err(ShellError::IoError(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(1),
+        Err(e) => {
+            let _ = match &shell.location {
+                Some(location) => writeln!(streams.stderr, "{}: {}", location, e),
+                None => writeln!(streams.stderr, "{}", e),
+            };
+            if std::mem::take(&mut shell.exit_on_error) {
+                return Err(ShellError::Exit(e.status()));
+            }
+            Ok(e.status())
+        }
+    }
+}
+
+/**
+ * Run a simple command after expanding its words and applying its redirections.
+ *
+ * A command that expands to no words has the status of its last command substitution, if any.
+ */
+fn run_simple(shell: &mut Shell, command: &SimpleCommand, streams: &mut Streams) -> Result<i32, ShellError> {
+    let words = expand_words(shell, &command.words)?;
+    apply_redirects(shell, &command.redirects, streams)?;
+    if words.is_empty() {
+        assign(shell, command)?;
+        return Ok(if has_substitution(command) { shell.last_status } else { 0 });
+    }
+
+    let saved = assign_temporarily(shell, command)?;
+    let result = shell.run_command(&words, streams);
+    for (name, var) in saved.into_iter().rev() {
+        shell.variables.restore(&name, var);
+    }
+    result
+}
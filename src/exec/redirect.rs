@@ -0,0 +1,121 @@
+use crate::exec::stream::{Input, Output, Streams};
+use crate::expansion::expand::expand_word;
+use crate::parser::ast::Redirect;
+use crate::parser::lexer::RedirectKind;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::messages::{BAD_FILE_DESCRIPTOR, IS_A_DIRECTORY, NO_SUCH_FILE, PERMISSION_DENIED};
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/**
+ * Apply the redirections of a command to its streams, from left to right.
+ *
+ * # Arguments
+ * * `shell` - The shell running the command, used to expand and resolve the targets.
+ * * `redirects` - The redirections of the command.
+ * * `streams` - The streams of the command.
+ *
+ * # Example
+ * ```rust
+ * use shell::exec::redirect::apply_redirects;
+ * use shell::exec::stream::Streams;
+ * use shell::parser::ast::Command;
+ * use shell::parser::lexer::tokenize;
+ * use shell::parser::parse::parse;
+ * use shell::shell::Shell;
+ *
+ * let list = parse(tokenize("ls > out.txt 2>&1").unwrap()).unwrap();
+ * let mut streams = Streams::inherit();
+ * if let Command::Simple(command) = &list.items[0].first.commands[0] {
+ *     apply_redirects(&mut Shell::new(), &command.redirects, &mut streams);
+ * }
+ * ```
+ */
+pub fn apply_redirects(shell: &mut Shell, redirects: &[Redirect], streams: &mut Streams) -> Result<(), ShellError> {
+    for redirect in redirects {
+        let target = expand_word(shell, &redirect.target)?;
+        let current_dir = shell.current_dir();
+        let mut options = OpenOptions::new();
+
+        let output_fd = !matches!(redirect.kind, RedirectKind::Input | RedirectKind::OutputAll | RedirectKind::AppendAll);
+        if output_fd && !matches!(redirect.fd, 1 | 2) {
+            return Err(bad_fd(redirect.fd));
+        }
+
+        match redirect.kind {
+            RedirectKind::Input => {
+                if redirect.fd != 0 {
+                    return Err(bad_fd(redirect.fd));
+                }
+                let file = open(current_dir, &target, options.read(true))?;
+                streams.stdin = Input::File(file);
+            }
+            RedirectKind::Output | RedirectKind::Append => {
+                let append = redirect.kind == RedirectKind::Append;
+                let file = open(current_dir, &target, options.create(true).append(append).write(true).truncate(!append))?;
+                set_output(streams, redirect.fd, Output::File(file))?;
+            }
+            RedirectKind::Duplicate => {
+                let output = match target.as_str() {
+                    "1" => streams.stdout.try_clone()?,
+                    "2" => streams.stderr.try_clone()?,
+                    _ if target.parse::<u32>().is_ok() => return Err(bad_fd_name(&target)),
+                    _ => {
+                        let file = open(current_dir, &target, options.create(true).write(true).truncate(true))?;
+                        if redirect.fd == 1 {
+                            streams.stderr = Output::File(file.try_clone()?);
+                        }
+                        Output::File(file)
+                    }
+                };
+                set_output(streams, redirect.fd, output)?;
+            }
+            RedirectKind::OutputAll | RedirectKind::AppendAll => {
+                let append = redirect.kind == RedirectKind::AppendAll;
+                let file = open(current_dir, &target, options.create(true).append(append).write(true).truncate(!append))?;
+                streams.stdout = Output::File(file.try_clone()?);
+                streams.stderr = Output::File(file);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Replace the output stream matching a file descriptor.
+ */
+fn set_output(streams: &mut Streams, fd: u32, output: Output) -> Result<(), ShellError> {
+    match fd {
+        1 => streams.stdout = output,
+        2 => streams.stderr = output,
+        _ => return Err(bad_fd(fd)),
+    }
+
+    Ok(())
+}
+
+/**
+ * Open the target of a redirection, with an error message naming the target.
+ */
+fn open(current_dir: &Path, target: &str, options: &OpenOptions) -> Result<File, ShellError> {
+    options.open(current_dir.join(target)).map_err(|e| {
+        let reason = match e.kind() {
+            ErrorKind::NotFound => NO_SUCH_FILE.to_owned(),
+            ErrorKind::PermissionDenied => PERMISSION_DENIED.to_owned(),
+            ErrorKind::IsADirectory => IS_A_DIRECTORY.to_owned(),
+            _ => e.to_string(),
+        };
+        ShellError::IoError(Error::new(e.kind(), format!("{}: {}", target, reason)))
+    })
+}
+
+fn bad_fd(fd: u32) -> ShellError {
+    bad_fd_name(&fd.to_string())
+}
+
+fn bad_fd_name(fd: &str) -> ShellError {
+    ShellError::IoError(Error::new(ErrorKind::InvalidInput, format!("{}: {BAD_FILE_DESCRIPTOR}", fd)))
+}
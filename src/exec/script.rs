@@ -0,0 +1,140 @@
+use crate::exec::stream::Streams;
+use crate::parser::parse::is_incomplete;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::path::get_home_dir;
+use std::fs::read_to_string;
+use std::io::{self, Write};
+use std::path::Path;
+
+/**
+ * The file run by interactive shells at startup, in the home directory.
+ */
+const RC_FILE: &str = ".0shellrc";
+
+/**
+ * The file run by login shells at startup, in the home directory.
+ */
+const PROFILE_FILE: &str = ".0shell_profile";
+
+/**
+ * Run the commands of a file in the shell.
+ *
+ * # Arguments
+ * * `shell` - The shell running the file, whose variables and directory are changed by it.
+ * * `path` - The file to run.
+ * * `streams` - The streams of the commands of the file.
+ *
+ * # Example
+ * ```rust
+ * use std::path::Path;
+ * use shell::exec::script::run_file;
+ * use shell::exec::stream::Streams;
+ * use shell::shell::Shell;
+ *
+ * let mut shell = Shell::new();
+ * let status = run_file(&mut shell, Path::new("setup.sh"), &Streams::inherit());
+ * ```
+ */
+pub fn run_file(shell: &mut Shell, path: &Path, streams: &Streams) -> Result<i32, ShellError> {
+    let content = read_to_string(path)?;
+    let mut lines = content.lines().map(str::to_owned);
+    run_script(shell, &path.display().to_string(), || Ok(lines.next()), streams)
+}
+
+/**
+ * Run the commands of a script in the shell, one line at a time as if they were typed,
+ * a command going on over the next lines while it is incomplete.
+ *
+ * A syntax error is printed after the name of the script and the number of its line, and stops
+ * the script with the status 2. `exit` stops the script too, and is returned so that the shell exits,
+ * as are `return`, `break` and `continue` for the function, file or loop running the script.
+ *
+ * Returns the status of the last command.
+ *
+ * # Arguments
+ * * `shell` - The shell running the script, whose variables and directory are changed by it.
+ * * `name` - The name of the script, shown before its errors.
+ * * `next_line` - Reads the next line of the script, or `None` at its end.
+ * * `streams` - The streams of the commands of the script.
+ */
+pub fn run_script(
+    shell: &mut Shell,
+    name: &str,
+    mut next_line: impl FnMut() -> io::Result<Option<String>>,
+    streams: &Streams,
+) -> Result<i32, ShellError> {
+    let outer = shell.location.take();
+    let result = run_lines(shell, name, &mut next_line, streams);
+    shell.location = outer;
+    result
+}
+
+fn run_lines(
+    shell: &mut Shell,
+    name: &str,
+    next_line: &mut impl FnMut() -> io::Result<Option<String>>,
+    streams: &Streams,
+) -> Result<i32, ShellError> {
+    let mut number = 0;
+    while let Some(mut command) = next_line()? {
+        number += 1;
+        let first = number;
+        while is_incomplete(&command) {
+            let Some(line) = next_line()? else {
+                break;
+            };
+            number += 1;
+            command.push('\n');
+            command.push_str(&line);
+        }
+        let command = command.trim();
+        if command.is_empty() {
+            continue;
+        }
+
+        let location = format!("{}:{}", name, first);
+        shell.location = Some(location.clone());
+        match shell.execute_with(command, streams) {
+            Ok(_) => (),
+            Err(e @ (ShellError::Exit(_) | ShellError::Return(_) | ShellError::Break(_) | ShellError::Continue(_) | ShellError::Interrupted)) => {
+                return Err(e);
+            }
+            Err(e) => {
+                writeln!(streams.stderr.try_clone()?, "{}: {}", location, e)?;
+                break;
+            }
+        }
+    }
+
+    Ok(shell.last_status)
+}
+
+/**
+ * Run the startup files of the shell that exist: `~/.0shell_profile` for a login shell,
+ * then `~/.0shellrc` for an interactive shell.
+ *
+ * # Arguments
+ * * `shell` - The shell being started.
+ * * `login` - Whether the shell is a login shell.
+ * * `interactive` - Whether the shell reads commands from a terminal.
+ */
+pub fn run_startup_files(shell: &mut Shell, login: bool, interactive: bool) -> Result<(), ShellError> {
+    let Ok(home) = get_home_dir(&shell.variables) else {
+        return Ok(());
+    };
+
+    for (enabled, file) in [(login, PROFILE_FILE), (interactive, RC_FILE)] {
+        let path = home.join(file);
+        if !enabled || !path.is_file() {
+            continue;
+        }
+        match run_file(shell, &path, &Streams::inherit()) {
+            Ok(_) => (),
+            Err(ShellError::Exit(code)) => return Err(ShellError::Exit(code)),
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io::{self, PipeReader, PipeWriter, Read, Write};
+use std::process::Stdio;
+
+/**
+ * Where a command reads its input from.
+ */
+#[derive(Debug)]
+pub enum Input {
+    Stdin,
+    Pipe(PipeReader),
+    File(File),
+}
+
+/**
+ * Where a command writes its output to.
+ */
+#[derive(Debug)]
+pub enum Output {
+    Stdout,
+    Stderr,
+    Pipe(PipeWriter),
+    File(File),
+}
+
+impl Input {
+    /**
+     * Duplicate the input so it can be handed to a child process.
+     */
+    pub fn try_clone(&self) -> io::Result<Input> {
+        Ok(match self {
+            Input::Stdin => Input::Stdin,
+            Input::Pipe(pipe) => Input::Pipe(pipe.try_clone()?),
+            Input::File(file) => Input::File(file.try_clone()?),
+        })
+    }
+
+    /**
+     * Convert the input into the stdin of a child process.
+     */
+    pub fn into_stdio(self) -> Stdio {
+        match self {
+            Input::Stdin => Stdio::inherit(),
+            Input::Pipe(pipe) => pipe.into(),
+            Input::File(file) => file.into(),
+        }
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Input::Stdin => io::stdin().read(buf),
+            Input::Pipe(pipe) => pipe.read(buf),
+            Input::File(file) => file.read(buf),
+        }
+    }
+}
+
+impl Output {
+    /**
+     * Duplicate the output so it can be handed to a child process.
+     */
+    pub fn try_clone(&self) -> io::Result<Output> {
+        Ok(match self {
+            Output::Stdout => Output::Stdout,
+            Output::Stderr => Output::Stderr,
+            Output::Pipe(pipe) => Output::Pipe(pipe.try_clone()?),
+            Output::File(file) => Output::File(file.try_clone()?),
+        })
+    }
+
+    /**
+     * Convert the output into the stdout or stderr of a child process.
+     */
+    pub fn into_stdio(self) -> Stdio {
+        match self {
+            Output::Stdout => io::stdout().into(),
+            Output::Stderr => io::stderr().into(),
+            Output::Pipe(pipe) => pipe.into(),
+            Output::File(file) => file.into(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout => io::stdout().write(buf),
+            Output::Stderr => io::stderr().write(buf),
+            Output::Pipe(pipe) => pipe.write(buf),
+            Output::File(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout => io::stdout().flush(),
+            Output::Stderr => io::stderr().flush(),
+            Output::Pipe(pipe) => pipe.flush(),
+            Output::File(file) => file.flush(),
+        }
+    }
+}
+
+/**
+ * The three standard streams of a command.
+ */
+#[derive(Debug)]
+pub struct Streams {
+    pub stdin: Input,
+    pub stdout: Output,
+    pub stderr: Output,
+}
+
+impl Streams {
+    /**
+     * Streams connected to the terminal of the shell.
+     */
+    pub fn inherit() -> Self {
+        Streams {
+            stdin: Input::Stdin,
+            stdout: Output::Stdout,
+            stderr: Output::Stderr,
+        }
+    }
+
+    /**
+     * Duplicate every stream so they can be handed to a child process.
+     */
+    pub fn try_clone(&self) -> io::Result<Streams> {
+        Ok(Streams {
+            stdin: self.stdin.try_clone()?,
+            stdout: self.stdout.try_clone()?,
+            stderr: self.stderr.try_clone()?,
+        })
+    }
+}
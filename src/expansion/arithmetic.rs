@@ -0,0 +1,526 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{
+    ARITHMETIC_OVERFLOW, ARITHMETIC_SYNTAX_ERROR, DIVISION_BY_ZERO, INVALID_NUMBER, NEGATIVE_EXPONENT, RECURSION_LEVEL_EXCEEDED,
+};
+use crate::variables::Variables;
+
+/**
+ * How deep variables may refer to other expressions, as in `a=b; b=a; echo $((a))`.
+ */
+const MAX_DEPTH: usize = 64;
+
+/**
+ * The operators of arithmetic expressions, longest first so that they are matched greedily.
+ */
+const OPERATORS: &[&str] = &[
+    "<<=", ">>=", "**", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=", "-=", "*=", "/=", "%=", "&=", "^=",
+    "|=", "+", "-", "*", "/", "%", "<", ">", "=", "!", "~", "&", "^", "|", "?", ":", ",", "(", ")",
+];
+
+const ASSIGNMENT_OPERATORS: &[&str] = &["=", "+=", "-=", "*=", "/=", "%=", "<<=", ">>=", "&=", "^=", "|="];
+
+/**
+ * The binary operators with their precedence, higher binding tighter.
+ */
+const BINARY_OPERATORS: &[(&str, u8)] = &[
+    ("||", 1),
+    ("&&", 2),
+    ("|", 3),
+    ("^", 4),
+    ("&", 5),
+    ("==", 6),
+    ("!=", 6),
+    ("<", 7),
+    (">", 7),
+    ("<=", 7),
+    (">=", 7),
+    ("<<", 8),
+    (">>", 8),
+    ("+", 9),
+    ("-", 9),
+    ("*", 10),
+    ("/", 10),
+    ("%", 10),
+    ("**", 11),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Name(String),
+    Operator(&'static str),
+}
+
+/**
+ * A parsed arithmetic expression.
+ */
+#[derive(Debug)]
+enum Expr {
+    Number(i64),
+    Variable(String),
+    Unary(&'static str, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Assign(String, &'static str, Box<Expr>),
+    Increment { name: String, delta: i64, prefix: bool },
+}
+
+/**
+ * Evaluate an arithmetic expression, as found in `$((...))`, `((...))` and `let`.
+ *
+ * Expressions use 64-bit signed integers and the operators of C, with `**` for powers.
+ * Variables are referred to by name, an unset or empty variable being 0, and can be assigned
+ * with `=`, `+=`, `++`... Overflows and divisions by zero are errors.
+ *
+ * # Arguments
+ * * `variables` - The variables of the shell.
+ * * `expression` - The expression to evaluate.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::arithmetic::evaluate;
+ * use shell::variables::Variables;
+ *
+ * let mut variables = Variables::default();
+ * variables.set("i", "4");
+ * assert_eq!(evaluate(&mut variables, "i += 2, i * 10").unwrap(), 60);
+ * assert_eq!(variables.get("i"), Some("6"));
+ * ```
+ */
+pub fn evaluate(variables: &mut Variables, expression: &str) -> Result<i64, ShellError> {
+    evaluate_at(variables, expression, 0)
+}
+
+fn evaluate_at(variables: &mut Variables, expression: &str, depth: usize) -> Result<i64, ShellError> {
+    if depth > MAX_DEPTH {
+        return Err(arithmetic_error(expression, RECURSION_LEVEL_EXCEEDED));
+    }
+
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Ok(0);
+    }
+
+    let mut parser = Parser {
+        expression,
+        tokens,
+        pos: 0,
+    };
+    let expr = parser.parse_comma()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.syntax_error());
+    }
+
+    Evaluator {
+        variables,
+        expression,
+        depth,
+    }
+    .eval(&expr)
+}
+
+/**
+ * Split an expression into numbers, names and operators, remembering where each token starts.
+ */
+fn tokenize(expression: &str) -> Result<Vec<(Token, usize)>, ShellError> {
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut rest = expression;
+
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        let start = expression.len() - rest.len();
+
+        if c.is_ascii_alphanumeric() || c == '_' {
+            let number = c.is_ascii_digit();
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || (number && (c == '#' || c == '@'))))
+                .unwrap_or(rest.len());
+            let text = &rest[..len];
+            let token = if number {
+                Token::Number(parse_number(text).ok_or_else(|| {
+                    arithmetic_error(expression, &format!("{INVALID_NUMBER} (error token is \"{}\")", text))
+                })?)
+            } else {
+                Token::Name(text.to_owned())
+            };
+            tokens.push((token, start));
+            rest = &rest[len..];
+            continue;
+        }
+
+        let Some(&operator) = OPERATORS.iter().find(|op| rest.starts_with(**op)) else {
+            return Err(arithmetic_error(expression, &format!("{ARITHMETIC_SYNTAX_ERROR} (error token is \"{}\")", rest)));
+        };
+        // `++` and `--` only increment next to a name, so that `1 ++ 2` is `1 + +2`.
+        let operator = match operator {
+            "++" | "--" if !next_to_name(tokens.last(), &rest[2..]) => &operator[..1],
+            _ => operator,
+        };
+        tokens.push((Token::Operator(operator), start));
+        rest = &rest[operator.len()..];
+    }
+
+    Ok(tokens)
+}
+
+fn next_to_name(previous: Option<&(Token, usize)>, rest: &str) -> bool {
+    matches!(previous, Some((Token::Name(_), _))) || rest.trim_start().starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+/**
+ * Parse an integer constant: decimal, octal with a leading `0`, hexadecimal with `0x`,
+ * or in any base from 2 to 64 as `base#digits`.
+ *
+ * Like in bash, the digits above 9 are the lowercase letters, then the uppercase letters, `@`
+ * and `_`. Up to base 36, uppercase and lowercase letters are the same digits.
+ */
+fn parse_number(text: &str) -> Option<i64> {
+    let (base, digits) = if let Some((base, digits)) = text.split_once('#') {
+        (base.parse().ok().filter(|base| (2..=64).contains(base))?, digits)
+    } else if let Some(digits) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (16, digits)
+    } else if text.len() > 1 && text.starts_with('0') {
+        (8, &text[1..])
+    } else {
+        (10, text)
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().try_fold(0i64, |value, c| {
+        let digit = match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 10,
+            'A'..='Z' if base <= 36 => c as u32 - 'A' as u32 + 10,
+            'A'..='Z' => c as u32 - 'A' as u32 + 36,
+            '@' => 62,
+            '_' => 63,
+            _ => return None,
+        };
+        if digit >= base {
+            return None;
+        }
+        value.checked_mul(base as i64)?.checked_add(digit as i64)
+    })
+}
+
+/**
+ * A recursive descent parser over the tokens of an expression, following the precedence of C.
+ */
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_operator(&self) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Operator(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, operator: &str) -> Result<(), ShellError> {
+        if self.peek_operator() != Some(operator) {
+            return Err(self.syntax_error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /**
+     * comma: assignment (',' assignment)*
+     */
+    fn parse_comma(&mut self) -> Result<Expr, ShellError> {
+        let mut expr = self.parse_assignment()?;
+        while self.peek_operator() == Some(",") {
+            self.pos += 1;
+            expr = Expr::Binary(",", Box::new(expr), Box::new(self.parse_assignment()?));
+        }
+        Ok(expr)
+    }
+
+    /**
+     * assignment: name assignment_operator assignment | conditional
+     */
+    fn parse_assignment(&mut self) -> Result<Expr, ShellError> {
+        if let (Some(Token::Name(name)), Some((Token::Operator(op), _))) = (self.peek(), self.tokens.get(self.pos + 1)) {
+            if ASSIGNMENT_OPERATORS.contains(op) {
+                let (name, op) = (name.clone(), *op);
+                self.pos += 2;
+                return Ok(Expr::Assign(name, op, Box::new(self.parse_assignment()?)));
+            }
+        }
+        self.parse_conditional()
+    }
+
+    /**
+     * conditional: binary ['?' comma ':' assignment]
+     */
+    fn parse_conditional(&mut self) -> Result<Expr, ShellError> {
+        let condition = self.parse_binary(1)?;
+        if self.peek_operator() != Some("?") {
+            return Ok(condition);
+        }
+
+        self.pos += 1;
+        let then = self.parse_comma()?;
+        self.expect(":")?;
+        let otherwise = self.parse_assignment()?;
+        Ok(Expr::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)))
+    }
+
+    /**
+     * Parse binary operators binding at least as tight as `min_precedence`.
+     * Every operator is left associative, except `**`.
+     */
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<Expr, ShellError> {
+        let mut lhs = self.parse_unary()?;
+
+        while let Some(&(op, precedence)) =
+            BINARY_OPERATORS.iter().find(|(op, _)| Some(*op) == self.peek_operator())
+        {
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let next = if op == "**" { precedence } else { precedence + 1 };
+            let rhs = self.parse_binary(next)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    /**
+     * unary: ('-' | '+' | '!' | '~') unary | ('++' | '--') name | postfix
+     */
+    fn parse_unary(&mut self) -> Result<Expr, ShellError> {
+        match self.peek_operator() {
+            Some(op @ ("-" | "+" | "!" | "~")) => {
+                self.pos += 1;
+                Ok(Expr::Unary(op, Box::new(self.parse_unary()?)))
+            }
+            Some(op @ ("++" | "--")) => {
+                self.pos += 1;
+                let Some(Token::Name(name)) = self.peek().cloned() else {
+                    return Err(self.syntax_error());
+                };
+                self.pos += 1;
+                let delta = if op == "++" { 1 } else { -1 };
+                Ok(Expr::Increment { name, delta, prefix: true })
+            }
+            _ => self.parse_postfix(),
+        }
+    }
+
+    /**
+     * postfix: name ('++' | '--') | primary
+     */
+    fn parse_postfix(&mut self) -> Result<Expr, ShellError> {
+        let expr = self.parse_primary()?;
+        if let Expr::Variable(name) = &expr {
+            if let Some(op @ ("++" | "--")) = self.peek_operator() {
+                self.pos += 1;
+                let delta = if op == "++" { 1 } else { -1 };
+                return Ok(Expr::Increment { name: name.clone(), delta, prefix: false });
+            }
+        }
+        Ok(expr)
+    }
+
+    /**
+     * primary: number | name | '(' comma ')'
+     */
+    fn parse_primary(&mut self) -> Result<Expr, ShellError> {
+        match self.peek().cloned() {
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                Ok(Expr::Number(n))
+            }
+            Some(Token::Name(name)) => {
+                self.pos += 1;
+                Ok(Expr::Variable(name))
+            }
+            Some(Token::Operator("(")) => {
+                self.pos += 1;
+                let expr = self.parse_comma()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    /**
+     * Build a syntax error showing the rest of the expression from the current token,
+     * or from the last one at the end of the expression.
+     */
+    fn syntax_error(&self) -> ShellError {
+        let start = self.tokens.get(self.pos).or(self.tokens.last()).map_or(0, |(_, start)| *start);
+        let message = format!("{ARITHMETIC_SYNTAX_ERROR} (error token is \"{}\")", self.expression[start..].trim_end());
+        arithmetic_error(self.expression, &message)
+    }
+}
+
+/**
+ * Evaluates a parsed expression, reading and assigning the variables of the shell.
+ */
+struct Evaluator<'a> {
+    variables: &'a mut Variables,
+    expression: &'a str,
+    depth: usize,
+}
+
+impl Evaluator<'_> {
+    fn eval(&mut self, expr: &Expr) -> Result<i64, ShellError> {
+        match expr {
+            Expr::Number(n) => Ok(*n),
+            Expr::Variable(name) => self.value_of(name),
+            Expr::Unary(op, operand) => {
+                let value = self.eval(operand)?;
+                match *op {
+                    "-" => value.checked_neg().ok_or_else(|| self.error(ARITHMETIC_OVERFLOW)),
+                    "!" => Ok((value == 0) as i64),
+                    "~" => Ok(!value),
+                    _ => Ok(value),
+                }
+            }
+            Expr::Binary("&&", lhs, rhs) => Ok((self.eval(lhs)? != 0 && self.eval(rhs)? != 0) as i64),
+            Expr::Binary("||", lhs, rhs) => Ok((self.eval(lhs)? != 0 || self.eval(rhs)? != 0) as i64),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(lhs)?;
+                let rhs = self.eval(rhs)?;
+                self.apply(op, lhs, rhs)
+            }
+            Expr::Conditional(condition, then, otherwise) => match self.eval(condition)? {
+                0 => self.eval(otherwise),
+                _ => self.eval(then),
+            },
+            Expr::Assign(name, op, value) => {
+                let value = self.eval(value)?;
+                let value = match op.strip_suffix('=').filter(|op| !op.is_empty()) {
+                    Some(op) => {
+                        let current = self.value_of(name)?;
+                        self.apply(op, current, value)?
+                    }
+                    None => value,
+                };
+                self.variables.set(name, &value.to_string());
+                Ok(value)
+            }
+            Expr::Increment { name, delta, prefix } => {
+                let current = self.value_of(name)?;
+                let value = current.checked_add(*delta).ok_or_else(|| self.error(ARITHMETIC_OVERFLOW))?;
+                self.variables.set(name, &value.to_string());
+                Ok(if *prefix { value } else { current })
+            }
+        }
+    }
+
+    /**
+     * Apply a binary operator, failing on overflows and divisions by zero.
+     */
+    fn apply(&self, op: &str, lhs: i64, rhs: i64) -> Result<i64, ShellError> {
+        let result = match op {
+            "+" => lhs.checked_add(rhs),
+            "-" => lhs.checked_sub(rhs),
+            "*" => lhs.checked_mul(rhs),
+            "/" | "%" if rhs == 0 => return Err(self.error(DIVISION_BY_ZERO)),
+            "/" => lhs.checked_div(rhs),
+            "%" => lhs.checked_rem(rhs),
+            "**" if rhs < 0 => return Err(self.error(NEGATIVE_EXPONENT)),
+            "**" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_pow(rhs)),
+            "<<" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shl(rhs)),
+            ">>" => u32::try_from(rhs).ok().and_then(|rhs| lhs.checked_shr(rhs)),
+            "&" => Some(lhs & rhs),
+            "^" => Some(lhs ^ rhs),
+            "|" => Some(lhs | rhs),
+            "==" => Some((lhs == rhs) as i64),
+            "!=" => Some((lhs != rhs) as i64),
+            "<" => Some((lhs < rhs) as i64),
+            ">" => Some((lhs > rhs) as i64),
+            "<=" => Some((lhs <= rhs) as i64),
+            ">=" => Some((lhs >= rhs) as i64),
+            _ => Some(rhs),
+        };
+
+        result.ok_or_else(|| self.error(ARITHMETIC_OVERFLOW))
+    }
+
+    /**
+     * Get the value of a variable, which may itself be an expression.
+     */
+    fn value_of(&mut self, name: &str) -> Result<i64, ShellError> {
+        match self.variables.get(name).map(str::to_owned) {
+            Some(value) if !value.trim().is_empty() => evaluate_at(self.variables, &value, self.depth + 1),
+            _ => Ok(0),
+        }
+    }
+
+    fn error(&self, message: &str) -> ShellError {
+        arithmetic_error(self.expression, message)
+    }
+}
+
+fn arithmetic_error(expression: &str, message: &str) -> ShellError {
+    ShellError::ExpansionError(format!("{}: {}", expression.trim(), message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> Result<i64, ShellError> {
+        evaluate(&mut Variables::default(), expression)
+    }
+
+    #[test]
+    fn follows_the_precedence_of_c() {
+        assert_eq!(eval("1 + 2 * 3").unwrap(), 7);
+        assert_eq!(eval("(1 + 2) * 3").unwrap(), 9);
+        assert_eq!(eval("2 ** 3 ** 2").unwrap(), 512);
+        assert_eq!(eval("-7 / 2").unwrap(), -3);
+        assert_eq!(eval("-7 % 3").unwrap(), -1);
+        assert_eq!(eval("1 < 2 && 3 > 4 || !0").unwrap(), 1);
+        assert_eq!(eval("0 ? 10 : 20").unwrap(), 20);
+        assert_eq!(eval("0x1f + 010").unwrap(), 39);
+    }
+
+    #[test]
+    fn reads_numbers_in_any_base() {
+        assert_eq!(eval("2#101 + 36#Zz").unwrap(), 5 + 35 * 36 + 35);
+        assert_eq!(eval("64#aA@_").unwrap(), ((10 * 64 + 36) * 64 + 62) * 64 + 63);
+        assert!(eval("8#9").is_err());
+        assert!(eval("65#1").is_err());
+    }
+
+    #[test]
+    fn reads_and_assigns_variables() {
+        let mut variables = Variables::default();
+        variables.set("i", "4");
+        assert_eq!(evaluate(&mut variables, "i += 2, i * 10").unwrap(), 60);
+        assert_eq!(variables.get("i"), Some("6"));
+
+        assert_eq!(evaluate(&mut variables, "i++").unwrap(), 6);
+        assert_eq!(evaluate(&mut variables, "--i").unwrap(), 6);
+        assert_eq!(evaluate(&mut variables, "unset + 1").unwrap(), 1);
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(eval("1 / 0").is_err());
+        assert!(eval("9223372036854775807 + 1").is_err());
+        assert!(eval("1 +").is_err());
+        assert!(eval("(1").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::parser::lexer::{Word, WordPart};
+
+/**
+ * A piece of a word during brace expansion: an unquoted character, which may be a brace or a comma,
+ * or any other part, which is copied as is.
+ */
+#[derive(Debug, Clone)]
+enum Item {
+    Char(char),
+    Part(WordPart),
+}
+
+/**
+ * Expand the braces of a word into several words, before any other expansion.
+ *
+ * `{a,b,c}` expands to each of its comma separated words, and `{x..y[..step]}` to a range of
+ * integers or letters. Integers are padded with zeros when either end starts with one,
+ * as in `{01..10}`. Braces can be nested, and the text around them is added to every word:
+ * `file{1..3}.txt` expands to `file1.txt file2.txt file3.txt`.
+ *
+ * Quoted braces and braces without a comma or a valid range are left untouched.
+ *
+ * # Arguments
+ * * `word` - The word to expand.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::brace::expand_braces;
+ * use shell::parser::lexer::{Word, WordPart};
+ *
+ * let word = Word { parts: vec![WordPart::Literal("src/{bin,lib}".to_owned())] };
+ * assert_eq!(expand_braces(&word).len(), 2);
+ * ```
+ */
+pub fn expand_braces(word: &Word) -> Vec<Word> {
+    let has_brace = word
+        .parts
+        .iter()
+        .any(|part| matches!(part, WordPart::Literal(s) if s.contains('{')));
+    if !has_brace {
+        return vec![word.clone()];
+    }
+
+    let mut items = Vec::new();
+    for part in &word.parts {
+        match part {
+            WordPart::Literal(s) => items.extend(s.chars().map(Item::Char)),
+            part => items.push(Item::Part(part.clone())),
+        }
+    }
+
+    expand(&items).iter().map(|items| to_word(items)).collect()
+}
+
+/**
+ * Expand the first valid brace expression of `items`, then recursively its alternatives and the rest.
+ */
+fn expand(items: &[Item]) -> Vec<Vec<Item>> {
+    for (open, item) in items.iter().enumerate() {
+        if !matches!(item, Item::Char('{')) {
+            continue;
+        }
+        let Some((close, commas)) = find_close(items, open) else {
+            continue;
+        };
+
+        let alternatives = if commas.is_empty() {
+            match range(&items[open + 1..close]) {
+                Some(alternatives) => alternatives,
+                None => continue,
+            }
+        } else {
+            let mut bounds = vec![open];
+            bounds.extend(&commas);
+            bounds.push(close);
+            bounds.windows(2).map(|bounds| items[bounds[0] + 1..bounds[1]].to_vec()).collect()
+        };
+
+        let suffixes = expand(&items[close + 1..]);
+        let mut words = Vec::new();
+        for alternative in alternatives {
+            for middle in expand(&alternative) {
+                for suffix in &suffixes {
+                    let mut word = items[..open].to_vec();
+                    word.extend(middle.iter().cloned());
+                    word.extend(suffix.iter().cloned());
+                    words.push(word);
+                }
+            }
+        }
+        return words;
+    }
+
+    vec![items.to_vec()]
+}
+
+/**
+ * Find the brace closing the one at `open`, and the positions of the commas directly inside of it.
+ */
+fn find_close(items: &[Item], open: usize) -> Option<(usize, Vec<usize>)> {
+    let mut depth = 0;
+    let mut commas = Vec::new();
+
+    for (i, item) in items.iter().enumerate().skip(open + 1) {
+        match item {
+            Item::Char('{') => depth += 1,
+            Item::Char('}') if depth == 0 => return Some((i, commas)),
+            Item::Char('}') => depth -= 1,
+            Item::Char(',') if depth == 0 => commas.push(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/**
+ * Generate the words of a `x..y[..step]` range, made of integers or single letters.
+ */
+fn range(items: &[Item]) -> Option<Vec<Vec<Item>>> {
+    let text = items
+        .iter()
+        .map(|item| match item {
+            Item::Char(c) => Some(*c),
+            Item::Part(_) => None,
+        })
+        .collect::<Option<String>>()?;
+
+    let bounds: Vec<&str> = text.split("..").collect();
+    let (start, end, step) = match bounds.as_slice() {
+        [start, end] => (*start, *end, 1),
+        [start, end, step] => (*start, *end, step.parse::<i64>().ok()?),
+        _ => return None,
+    };
+    let step = step.unsigned_abs().max(1);
+
+    let words: Vec<String> = if let (Ok(first), Ok(last)) = (start.parse::<i64>(), end.parse::<i64>()) {
+        let padded = |s: &str| s.trim_start_matches('-').len() > 1 && s.trim_start_matches('-').starts_with('0');
+        let width = if padded(start) || padded(end) { start.len().max(end.len()) } else { 0 };
+        steps(first, last, step).map(|n| format!("{:0width$}", n)).collect()
+    } else {
+        let (mut first, mut last) = (start.chars(), end.chars());
+        let (Some(first), None, Some(last), None) = (first.next(), first.next(), last.next(), last.next()) else {
+            return None;
+        };
+        if !first.is_ascii_alphabetic() || !last.is_ascii_alphabetic() {
+            return None;
+        }
+        steps(first as i64, last as i64, step)
+            .filter_map(|c| char::from_u32(c as u32))
+            .map(String::from)
+            .collect()
+    };
+
+    Some(words.iter().map(|word| word.chars().map(Item::Char).collect()).collect())
+}
+
+/**
+ * Count from `first` to `last` included, up or down.
+ */
+fn steps(first: i64, last: i64, step: u64) -> impl Iterator<Item = i64> {
+    let count = first.abs_diff(last) / step + 1;
+    let step = if first <= last { step as i64 } else { -(step as i64) };
+    (0..count as i64).map(move |i| first + i * step)
+}
+
+/**
+ * Join the items back into a word.
+ */
+fn to_word(items: &[Item]) -> Word {
+    let mut word = Word::default();
+    for item in items {
+        match (item, word.parts.last_mut()) {
+            (Item::Char(c), Some(WordPart::Literal(s))) => s.push(*c),
+            (Item::Char(c), _) => word.parts.push(WordPart::Literal(c.to_string())),
+            (Item::Part(part), _) => word.parts.push(part.clone()),
+        }
+    }
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_text(text: &str) -> Vec<String> {
+        let word = Word { parts: vec![WordPart::Literal(text.to_owned())] };
+        expand_braces(&word).iter().map(Word::text).collect()
+    }
+
+    #[test]
+    fn expands_lists() {
+        assert_eq!(expand_text("src/{bin,lib}.rs"), ["src/bin.rs", "src/lib.rs"]);
+        assert_eq!(expand_text("{a,b{1,2}}"), ["a", "b1", "b2"]);
+        assert_eq!(expand_text("x{,y}"), ["x", "xy"]);
+    }
+
+    #[test]
+    fn expands_ranges() {
+        assert_eq!(expand_text("file{1..3}.txt"), ["file1.txt", "file2.txt", "file3.txt"]);
+        assert_eq!(expand_text("{3..1}"), ["3", "2", "1"]);
+        assert_eq!(expand_text("{01..10..3}"), ["01", "04", "07", "10"]);
+        assert_eq!(expand_text("{a..e..2}"), ["a", "c", "e"]);
+    }
+
+    #[test]
+    fn leaves_invalid_and_quoted_braces() {
+        assert_eq!(expand_text("{a}"), ["{a}"]);
+        assert_eq!(expand_text("{1..x}"), ["{1..x}"]);
+        assert_eq!(expand_text("{}"), ["{}"]);
+
+        let word = Word { parts: vec![WordPart::Quoted("{a,b}".to_owned())] };
+        assert_eq!(expand_braces(&word), vec![word]);
+    }
+}
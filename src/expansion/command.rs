@@ -0,0 +1,65 @@
+use crate::exec::list::run_list;
+use crate::exec::stream::{Input, Output, Streams};
+use crate::parser::lexer::tokenize;
+use crate::parser::parse::parse;
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use std::io::{pipe, Read};
+use std::thread;
+
+/**
+ * Run the command of a `$(...)` substitution and return what it printed.
+ *
+ * The command runs in a copy of the shell, so that it cannot change its variables or directory,
+ * and its output is read from a pipe while it runs. Trailing newlines are removed from the output.
+ *
+ * The status of the command becomes `$?`.
+ *
+ * # Arguments
+ * * `shell` - The shell running the substitution.
+ * * `source` - The command to run.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::command::substitute_command;
+ * use shell::shell::Shell;
+ *
+ * let mut shell = Shell::new();
+ * assert_eq!(substitute_command(&mut shell, "echo hello").unwrap(), "hello");
+ * ```
+ */
+pub fn substitute_command(shell: &mut Shell, source: &str) -> Result<String, ShellError> {
+    let list = parse(tokenize(source)?)?;
+    let mut subshell = shell.clone();
+    let (mut reader, writer) = pipe()?;
+    let streams = Streams {
+        stdin: Input::Stdin,
+        stdout: Output::Pipe(writer),
+        stderr: Output::Stderr,
+    };
+
+    let (result, output) = thread::scope(|scope| {
+        let handle = scope.spawn(move || {
+            let mut output = Vec::new();
+            reader.read_to_end(&mut output).map(|_| output)
+        });
+
+        let result = run_list(&mut subshell, &list, &streams);
+        drop(streams);
+        (result, handle.join())
+    });
+
+    shell.last_status = match result {
+        Ok(status) | Err(ShellError::Exit(status) | ShellError::Return(status)) => status,
+        Err(ShellError::Break(_) | ShellError::Continue(_)) => 0,
+        Err(e @ ShellError::Interrupted) => e.status(),
+        Err(e) => {
+            eprintln!("{}", e);
+            e.status()
+        }
+    };
+
+    let output = output.unwrap_or_else(|_| Ok(Vec::new()))?;
+    let output = String::from_utf8_lossy(&output);
+    Ok(output.trim_end_matches('\n').to_owned())
+}
@@ -0,0 +1,378 @@
+use crate::expansion::arithmetic::evaluate;
+use crate::expansion::brace::expand_braces;
+use crate::expansion::command::substitute_command;
+use crate::expansion::glob::glob;
+use crate::expansion::param::expand_param;
+use crate::expansion::pattern::escape;
+use crate::parser::lexer::{Word, WordPart};
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::messages::NO_MATCH;
+use crate::utils::path::{get_home_dir, get_user_home_dir};
+use std::path::PathBuf;
+
+/**
+ * The default field separators, used when `IFS` is not set.
+ */
+const DEFAULT_IFS: &str = " \t\n";
+
+/**
+ * Expand a word into a single string, replacing a leading `~` and parameters with their values.
+ *
+ * No field splitting happens, which is what assignments and redirection targets need.
+ *
+ * # Arguments
+ * * `shell` - The shell holding the parameters.
+ * * `word` - The word to expand.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::expand::expand_word;
+ * use shell::parser::lexer::{Word, WordPart};
+ * use shell::shell::Shell;
+ *
+ * let mut shell = Shell::new();
+ * let word = Word { parts: vec![WordPart::Param { name: "?".to_owned(), op: None, quoted: false }] };
+ * assert_eq!(expand_word(&mut shell, &word).unwrap(), "0");
+ * ```
+ */
+pub fn expand_word(shell: &mut Shell, word: &Word) -> Result<String, ShellError> {
+    let word = expand_tilde(shell, word, false);
+    let mut text = String::new();
+
+    for part in &word.parts {
+        match part {
+            WordPart::Literal(s) | WordPart::Quoted(s) => text.push_str(s),
+            WordPart::Param { name, op, .. } => text.push_str(&expand_param(shell, name, op.as_deref())?),
+            WordPart::Command { source, .. } => text.push_str(&substitute_command(shell, source)?),
+            WordPart::Arithmetic { expression, .. } => text.push_str(&expand_arithmetic(shell, expression)?),
+        }
+    }
+
+    Ok(text)
+}
+
+/**
+ * Expand the value of an assignment such as `PATH=~/bin:~/.local/bin`.
+ *
+ * Like `expand_word`, except that a `~` is also expanded after every `:`.
+ */
+pub fn expand_assignment(shell: &mut Shell, word: &Word) -> Result<String, ShellError> {
+    let word = expand_tilde(shell, word, true);
+    expand_word(shell, &word)
+}
+
+/**
+ * Expand a word used as a pattern, such as in `${name#pattern}`.
+ *
+ * Quoted parts are escaped so that their special characters only match themselves.
+ */
+pub fn expand_pattern(shell: &mut Shell, word: &Word) -> Result<String, ShellError> {
+    let mut pattern = String::new();
+
+    for part in &word.parts {
+        match part {
+            WordPart::Literal(s) => pattern.push_str(s),
+            WordPart::Quoted(s) => pattern.push_str(&escape(s)),
+            WordPart::Param { name, op, quoted } => {
+                let value = expand_param(shell, name, op.as_deref())?;
+                pattern.push_str(&if *quoted { escape(&value) } else { value });
+            }
+            WordPart::Command { source, quoted } => {
+                let output = substitute_command(shell, source)?;
+                pattern.push_str(&if *quoted { escape(&output) } else { output });
+            }
+            WordPart::Arithmetic { expression, .. } => pattern.push_str(&expand_arithmetic(shell, expression)?),
+        }
+    }
+
+    Ok(pattern)
+}
+
+/**
+ * Expand every word of a command into its arguments.
+ *
+ * Braces are expanded first, turning a word into several ones, then a leading `~`. The results of unquoted parameter
+ * expansions and command substitutions are then split into several fields on the characters of `IFS`,
+ * and words that expand to nothing unquoted are dropped. `"$@"` expands to one field per positional parameter,
+ * and to no field at all when there are none.
+ * Fields with unquoted `*`, `?` or `[` are then replaced by the sorted paths they match, if any.
+ *
+ * # Arguments
+ * * `shell` - The shell holding the parameters.
+ * * `words` - The words to expand.
+ */
+pub fn expand_words(shell: &mut Shell, words: &[Word]) -> Result<Vec<String>, ShellError> {
+    let ifs = shell.variables.get("IFS").unwrap_or(DEFAULT_IFS).to_owned();
+    let mut fields = Vec::new();
+
+    for word in words.iter().flat_map(expand_braces) {
+        if shell.positional.is_empty() && is_quoted_at(&word) {
+            continue;
+        }
+
+        let word = expand_tilde(shell, &word, false);
+        let mut splitter = FieldSplitter::new(&ifs);
+        for part in &word.parts {
+            match part {
+                WordPart::Literal(s) => splitter.push_str(s, false),
+                WordPart::Quoted(s) => splitter.push_str(s, true),
+                WordPart::Param { name, op: None, quoted: true } if name == "@" => {
+                    splitter.push_fields(&shell.positional);
+                }
+                WordPart::Param { name, op, quoted } => {
+                    let value = expand_param(shell, name, op.as_deref())?;
+                    splitter.push(&value, *quoted);
+                }
+                WordPart::Command { source, quoted } => {
+                    let output = substitute_command(shell, source)?;
+                    splitter.push(&output, *quoted);
+                }
+                WordPart::Arithmetic { expression, quoted } => {
+                    let value = expand_arithmetic(shell, expression)?;
+                    splitter.push(&value, *quoted);
+                }
+            }
+        }
+
+        for field in splitter.finish() {
+            fields.extend(expand_glob(shell, field)?);
+        }
+    }
+
+    Ok(fields)
+}
+
+/**
+ * Check whether a word is only a quoted `$@`, as in `"$@"`, which expands to no field at all
+ * without positional parameters.
+ */
+fn is_quoted_at(word: &Word) -> bool {
+    let mut found = false;
+    for part in &word.parts {
+        match part {
+            WordPart::Param { name, op: None, quoted: true } if name == "@" => found = true,
+            WordPart::Quoted(s) if s.is_empty() => {}
+            _ => return false,
+        }
+    }
+    found
+}
+
+/**
+ * Replace a field by the paths its pattern matches.
+ *
+ * A pattern that matches nothing is kept as is, unless the `nullglob` or `failglob` option is set.
+ */
+fn expand_glob(shell: &Shell, field: Field) -> Result<Vec<String>, ShellError> {
+    if !field.glob {
+        return Ok(vec![field.text]);
+    }
+
+    let paths = glob(shell.current_dir(), &field.pattern);
+    if !paths.is_empty() {
+        Ok(paths)
+    } else if shell.options.failglob {
+        Err(ShellError::ExpansionError(format!("{NO_MATCH}: {}", field.text)))
+    } else if shell.options.nullglob {
+        Ok(Vec::new())
+    } else {
+        Ok(vec![field.text])
+    }
+}
+
+/**
+ * Replace the tilde prefixes of a word with the directories they name, as quoted text.
+ *
+ * A tilde prefix is an unquoted `~` at the start of the word, or after a `:` in assignments,
+ * followed by the characters up to the next `/`:
+ * * `~` is the home directory of the current user, from `HOME`.
+ * * `~user` is the home directory of `user`, from /etc/passwd.
+ * * `~+` is the current directory and `~-` the previous one, from `OLDPWD`.
+ *
+ * Prefixes that cannot be resolved are left as they are.
+ */
+fn expand_tilde(shell: &Shell, word: &Word, assignment: bool) -> Word {
+    let mut expanded = Word::default();
+
+    for (i, part) in word.parts.iter().enumerate() {
+        let WordPart::Literal(text) = part else {
+            expanded.parts.push(part.clone());
+            continue;
+        };
+        if i > 0 && !assignment {
+            expanded.parts.push(part.clone());
+            continue;
+        }
+
+        let mut literal = String::new();
+        let segments: Vec<&str> = if assignment { text.split(':').collect() } else { vec![text] };
+        for (j, segment) in segments.iter().enumerate() {
+            if j > 0 {
+                literal.push(':');
+            }
+
+            let at_start = i == 0 || j > 0;
+            let ends_prefix = j + 1 < segments.len() || i + 1 == word.parts.len();
+            let (prefix, rest) = match segment.find('/') {
+                Some(slash) => segment.split_at(slash),
+                None if ends_prefix => (*segment, ""),
+                None => ("", *segment),
+            };
+
+            match prefix.strip_prefix('~').filter(|_| at_start).and_then(|name| tilde_dir(shell, name)) {
+                Some(dir) => {
+                    if !literal.is_empty() {
+                        expanded.parts.push(WordPart::Literal(std::mem::take(&mut literal)));
+                    }
+                    expanded.parts.push(WordPart::Quoted(dir));
+                    literal.push_str(rest);
+                }
+                None => literal.push_str(segment),
+            }
+        }
+
+        if !literal.is_empty() {
+            expanded.parts.push(WordPart::Literal(literal));
+        }
+    }
+
+    expanded
+}
+
+/**
+ * Get the directory named by a tilde prefix, without its `~`.
+ */
+fn tilde_dir(shell: &Shell, name: &str) -> Option<String> {
+    let dir = match name {
+        "" => get_home_dir(&shell.variables).ok()?,
+        "+" => shell.current_dir().to_path_buf(),
+        "-" => PathBuf::from(shell.variables.get("OLDPWD")?),
+        user => get_user_home_dir(user)?,
+    };
+    Some(dir.to_string_lossy().into_owned())
+}
+
+/**
+ * Expand the expression of a `$((...))` expansion and evaluate it.
+ */
+fn expand_arithmetic(shell: &mut Shell, expression: &Word) -> Result<String, ShellError> {
+    let expression = expand_word(shell, expression)?;
+    Ok(evaluate(&mut shell.variables, &expression)?.to_string())
+}
+
+/**
+ * A field produced by the expansion of a word, along with the pattern it may be matched as.
+ */
+#[derive(Default)]
+struct Field {
+    text: String,
+    /// The text with its quoted special characters escaped.
+    pattern: String,
+    /// Whether the field has unquoted special characters.
+    glob: bool,
+}
+
+impl Field {
+    fn push(&mut self, c: char, quoted: bool) {
+        self.text.push(c);
+        if quoted && matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            self.pattern.push('\\');
+        } else if !quoted && matches!(c, '*' | '?' | '[') {
+            self.glob = true;
+        }
+        self.pattern.push(c);
+    }
+}
+
+/**
+ * Builds the fields of a single word, splitting the results of unquoted expansions.
+ */
+struct FieldSplitter<'a> {
+    ifs: &'a str,
+    fields: Vec<Field>,
+    current: Field,
+    started: bool,
+}
+
+impl<'a> FieldSplitter<'a> {
+    fn new(ifs: &'a str) -> Self {
+        FieldSplitter {
+            ifs,
+            fields: Vec::new(),
+            current: Field::default(),
+            started: false,
+        }
+    }
+
+    /**
+     * Append text that is never split.
+     */
+    fn push_str(&mut self, s: &str, quoted: bool) {
+        for c in s.chars() {
+            self.current.push(c, quoted);
+        }
+        self.started = true;
+    }
+
+    /**
+     * Append the result of an expansion, which is split unless it was quoted.
+     */
+    fn push(&mut self, s: &str, quoted: bool) {
+        if quoted {
+            self.push_str(s, true);
+        } else {
+            self.push_split(s);
+        }
+    }
+
+    /**
+     * Append values that each make a separate field, the first one joining the text before it
+     * and the last one the text after it.
+     */
+    fn push_fields(&mut self, values: &[String]) {
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.fields.push(std::mem::take(&mut self.current));
+            }
+            self.push_str(value, true);
+        }
+    }
+
+    /**
+     * Append text that is split on `IFS`.
+     *
+     * Runs of `IFS` whitespace count as a single separator, while every other `IFS` character
+     * ends a field, possibly empty.
+     */
+    fn push_split(&mut self, s: &str) {
+        let mut after_delimiter = false;
+        for c in s.chars() {
+            if !self.ifs.contains(c) {
+                self.current.push(c, false);
+                self.started = true;
+                after_delimiter = false;
+            } else if c.is_whitespace() {
+                self.end_field();
+            } else {
+                if !self.started || after_delimiter {
+                    self.fields.push(Field::default());
+                }
+                self.end_field();
+                after_delimiter = true;
+            }
+        }
+    }
+
+    fn end_field(&mut self) {
+        if self.started {
+            self.fields.push(std::mem::take(&mut self.current));
+            self.started = false;
+        }
+    }
+
+    fn finish(mut self) -> Vec<Field> {
+        self.end_field();
+        self.fields
+    }
+}
@@ -0,0 +1,119 @@
+use crate::expansion::pattern::{has_wildcards, matches, unescape};
+use std::fs::read_dir;
+use std::path::Path;
+
+/**
+ * Expand a pattern into the sorted paths it matches.
+ *
+ * The pattern is matched one path component at a time against the entries of the directories,
+ * relative to `current_dir` unless it starts with `/`. Entries starting with a dot are only matched
+ * by components starting with a dot, and a `**` component matches any number of directories.
+ *
+ * Returns an empty list when nothing matches.
+ *
+ * # Arguments
+ * * `current_dir` - The directory relative patterns are matched from.
+ * * `pattern` - The pattern, with its quoted special characters escaped.
+ *
+ * # Example
+ * ```rust
+ * use std::path::Path;
+ * use shell::expansion::glob::glob;
+ *
+ * let sources = glob(Path::new("."), "src/**/*.rs");
+ * ```
+ */
+pub fn glob(current_dir: &Path, pattern: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    match pattern.strip_prefix('/') {
+        Some(rest) => {
+            let components: Vec<&str> = rest.split('/').collect();
+            expand(Path::new("/"), "/", &components, &mut paths);
+        }
+        None => {
+            let components: Vec<&str> = pattern.split('/').collect();
+            expand(current_dir, "", &components, &mut paths);
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/**
+ * Match the remaining components of a pattern inside `dir`, shown as `shown` in the results.
+ */
+fn expand(dir: &Path, shown: &str, components: &[&str], paths: &mut Vec<String>) {
+    let Some((&component, rest)) = components.split_first() else {
+        return;
+    };
+
+    // A trailing slash only keeps directories, which is all we descend into.
+    if component.is_empty() {
+        if rest.is_empty() {
+            paths.push(shown.to_owned());
+        } else {
+            expand(dir, shown, rest, paths);
+        }
+        return;
+    }
+
+    if component == "**" {
+        expand(dir, shown, rest, paths);
+        for name in entries(dir, false) {
+            let path = dir.join(&name);
+            if rest.is_empty() {
+                paths.push(format!("{}{}", shown, name));
+            }
+            if path.symlink_metadata().is_ok_and(|metadata| metadata.is_dir()) {
+                expand(&path, &format!("{}{}/", shown, name), components, paths);
+            }
+        }
+        return;
+    }
+
+    if !has_wildcards(component) {
+        let name = unescape(component);
+        descend(dir, shown, &name, rest, paths);
+        return;
+    }
+
+    let hidden = component.starts_with('.') || component.starts_with("\\.");
+    for name in entries(dir, hidden) {
+        if matches(component, &name) {
+            descend(dir, shown, &name, rest, paths);
+        }
+    }
+}
+
+/**
+ * Add the entry `name` of `dir` to the results, or match the rest of the pattern inside of it.
+ */
+fn descend(dir: &Path, shown: &str, name: &str, rest: &[&str], paths: &mut Vec<String>) {
+    let path = dir.join(name);
+    if rest.is_empty() {
+        if path.symlink_metadata().is_ok() {
+            paths.push(format!("{}{}", shown, name));
+        }
+    } else if path.is_dir() {
+        expand(&path, &format!("{}{}/", shown, name), rest, paths);
+    }
+}
+
+/**
+ * Get the names of the entries of a directory, with or without the hidden ones.
+ */
+fn entries(dir: &Path, hidden: bool) -> Vec<String> {
+    let Ok(entries) = read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .map_while(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| hidden || !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
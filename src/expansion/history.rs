@@ -0,0 +1,368 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{BAD_WORD_SPECIFIER, EVENT_NOT_FOUND, SUBSTITUTION_FAILED};
+
+/**
+ * Which words of a history entry a designator such as `:2-4` selects.
+ *
+ * `None` as the end stands for the last word, and `skip_last` drops it as in `:2-`.
+ */
+struct Words {
+    start: usize,
+    end: Option<usize>,
+    skip_last: bool,
+    /// `*` selects nothing rather than failing when there are no arguments.
+    allow_empty: bool,
+}
+
+/**
+ * Expand the history references of a command line, before it is parsed.
+ *
+ * `!!` is the previous line, `!n` the line `n`, `!-n` the `n`th line before the current one,
+ * `!string` the last line starting with `string` and `!?string?` the last line containing it.
+ * Words of a line are selected with a designator after `:`, like `!!:2` or `!-2:1-3`, while
+ * `!$`, `!^` and `!*` stand for the last word, the first argument and every argument of the
+ * previous line. `^old^new` at the start of a line repeats the previous line, replacing `old` with `new`.
+ *
+ * Nothing is expanded inside single quotes, after a backslash, or when `!` is followed by a blank, `=` or `(`.
+ *
+ * Returns the expanded line, or `None` if the line has no history reference.
+ *
+ * # Arguments
+ * * `line` - The command line.
+ * * `entries` - The previous command lines, oldest first.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::history::expand_history;
+ *
+ * let entries = vec!["cp notes.txt backup/".to_owned()];
+ * assert_eq!(expand_history("ls !$", &entries).unwrap(), Some("ls backup/".to_owned()));
+ * ```
+ */
+pub fn expand_history(line: &str, entries: &[String]) -> Result<Option<String>, ShellError> {
+    if let Some(rest) = line.strip_prefix('^') {
+        return quick_substitution(rest, entries).map(Some);
+    }
+
+    let chars: Vec<char> = line.chars().collect();
+    let mut expanded = String::new();
+    let mut changed = false;
+    let mut in_single_quotes = false;
+    let mut in_double_quotes = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            '\\' if !in_single_quotes && i + 1 < chars.len() => {
+                expanded.push(c);
+                expanded.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '!' if !in_single_quotes && starts_reference(chars.get(i + 1)) => {
+                let (text, end) = expand_reference(&chars, i + 1, entries)?;
+                expanded.push_str(&text);
+                changed = true;
+                i = end;
+                continue;
+            }
+            _ => {}
+        }
+        expanded.push(c);
+        i += 1;
+    }
+
+    Ok(changed.then_some(expanded))
+}
+
+fn starts_reference(next: Option<&char>) -> bool {
+    match next {
+        None => false,
+        Some(c) => !c.is_whitespace() && !matches!(c, '=' | '(' | '"'),
+    }
+}
+
+/**
+ * Expand the reference starting right after a `!`.
+ *
+ * Returns the text of the reference and the position right after it.
+ */
+fn expand_reference(chars: &[char], start: usize, entries: &[String]) -> Result<(String, usize), ShellError> {
+    let mut i = start;
+    let previous = entries.len().checked_sub(1);
+    let read_number = |i: &mut usize| {
+        let begin = *i;
+        while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+        chars[begin..*i].iter().collect::<String>().parse::<usize>().ok()
+    };
+
+    let (index, short_designator) = match chars[i] {
+        '!' => {
+            i += 1;
+            (previous, true)
+        }
+        '$' | '^' | '*' | ':' => (previous, true),
+        '-' if chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+            i += 1;
+            let n = read_number(&mut i).filter(|&n| n > 0);
+            (n.and_then(|n| entries.len().checked_sub(n)), true)
+        }
+        c if c.is_ascii_digit() => {
+            let n = read_number(&mut i).filter(|&n| n > 0);
+            (n.map(|n| n - 1), true)
+        }
+        '?' => {
+            let begin = i + 1;
+            i = begin;
+            while i < chars.len() && chars[i] != '?' {
+                i += 1;
+            }
+            let text: String = chars[begin..i].iter().collect();
+            i = (i + 1).min(chars.len());
+            (entries.iter().rposition(|entry| entry.contains(&text)), true)
+        }
+        _ => {
+            while i < chars.len() && !chars[i].is_whitespace() && !matches!(chars[i], ':' | '"' | '\'' | ';' | '|' | '&') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            (entries.iter().rposition(|entry| entry.starts_with(&text)), false)
+        }
+    };
+
+    let reference: String = chars[start - 1..i].iter().collect();
+    let Some(entry) = index.and_then(|index| entries.get(index)) else {
+        return Err(ShellError::ExpansionError(format!("{}: {EVENT_NOT_FOUND}", reference)));
+    };
+
+    let designator = match chars.get(i) {
+        Some(':') => {
+            i += 1;
+            Some(parse_designator(chars, &mut i))
+        }
+        Some('$' | '^' | '*') if short_designator => Some(parse_designator(chars, &mut i)),
+        _ => None,
+    };
+
+    let text = match designator {
+        None => entry.clone(),
+        Some(words) => {
+            let reference: String = chars[start - 1..i].iter().collect();
+            select_words(entry, words.ok_or_else(|| bad_word_specifier(&reference))?)
+                .ok_or_else(|| bad_word_specifier(&reference))?
+        }
+    };
+
+    Ok((text, i))
+}
+
+/**
+ * Parse a word designator: `n`, `^`, `$`, `x-y`, `x-`, `-y`, `x*` or `*`.
+ */
+fn parse_designator(chars: &[char], i: &mut usize) -> Option<Words> {
+    let read_word = |i: &mut usize| -> Option<Option<usize>> {
+        match chars.get(*i)? {
+            '^' => {
+                *i += 1;
+                Some(Some(1))
+            }
+            '$' => {
+                *i += 1;
+                Some(None)
+            }
+            c if c.is_ascii_digit() => {
+                let begin = *i;
+                while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+                    *i += 1;
+                }
+                chars[begin..*i].iter().collect::<String>().parse().ok().map(Some)
+            }
+            _ => None,
+        }
+    };
+
+    if chars.get(*i) == Some(&'*') {
+        *i += 1;
+        return Some(Words {
+            start: 1,
+            end: None,
+            skip_last: false,
+            allow_empty: true,
+        });
+    }
+
+    let start = if chars.get(*i) == Some(&'-') { Some(0) } else { read_word(i)? };
+    let single = |start: Option<usize>| Words {
+        start: start.unwrap_or(usize::MAX),
+        end: start,
+        skip_last: false,
+        allow_empty: false,
+    };
+
+    match chars.get(*i) {
+        Some('*') => {
+            *i += 1;
+            Some(Words {
+                start: start?,
+                end: None,
+                skip_last: false,
+                allow_empty: true,
+            })
+        }
+        Some('-') => {
+            *i += 1;
+            match read_word(i) {
+                Some(end) => Some(Words {
+                    start: start?,
+                    end,
+                    skip_last: false,
+                    allow_empty: false,
+                }),
+                None => Some(Words {
+                    start: start?,
+                    end: None,
+                    skip_last: true,
+                    allow_empty: false,
+                }),
+            }
+        }
+        _ => Some(single(start)),
+    }
+}
+
+/**
+ * Get the words of a history entry selected by a designator, joined with spaces.
+ */
+fn select_words(entry: &str, words: Words) -> Option<String> {
+    let all = split_words(entry);
+    let last = all.len().checked_sub(1)?;
+    let start = if words.start == usize::MAX { last } else { words.start };
+    let mut end = words.end.unwrap_or(last);
+    if words.skip_last {
+        end = end.checked_sub(1)?;
+    }
+
+    if start > end || end > last {
+        return words.allow_empty.then(String::new);
+    }
+    Some(all[start..=end].join(" "))
+}
+
+/**
+ * Split a command line into words on blanks, keeping quoted text together.
+ */
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                word.push(c);
+                word.extend(chars.next());
+            }
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                word.push(c);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                word.push(c);
+            }
+            (c, None) if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/**
+ * Expand `^old^new^rest`, the leading `^` being already removed.
+ */
+fn quick_substitution(line: &str, entries: &[String]) -> Result<String, ShellError> {
+    let Some(previous) = entries.last() else {
+        return Err(ShellError::ExpansionError(format!("^{}: {EVENT_NOT_FOUND}", line)));
+    };
+
+    let (old, rest) = line.split_once('^').unwrap_or((line, ""));
+    let (new, rest) = rest.split_once('^').unwrap_or((rest, ""));
+    if old.is_empty() || !previous.contains(old) {
+        return Err(ShellError::ExpansionError(format!("{}: {SUBSTITUTION_FAILED}", old)));
+    }
+
+    Ok(format!("{}{}", previous.replacen(old, new, 1), rest))
+}
+
+fn bad_word_specifier(reference: &str) -> ShellError {
+    ShellError::ExpansionError(format!("{}: {BAD_WORD_SPECIFIER}", reference))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<String> {
+        ["echo one", "cp notes.txt backup/", "grep -n todo src/main.rs"].map(str::to_owned).to_vec()
+    }
+
+    fn expand(line: &str) -> Result<Option<String>, ShellError> {
+        expand_history(line, &entries())
+    }
+
+    #[test]
+    fn expands_events() {
+        assert_eq!(expand("!!").unwrap().as_deref(), Some("grep -n todo src/main.rs"));
+        assert_eq!(expand("!1").unwrap().as_deref(), Some("echo one"));
+        assert_eq!(expand("!-2").unwrap().as_deref(), Some("cp notes.txt backup/"));
+        assert_eq!(expand("!cp").unwrap().as_deref(), Some("cp notes.txt backup/"));
+        assert_eq!(expand("!?todo?").unwrap().as_deref(), Some("grep -n todo src/main.rs"));
+        assert_eq!(expand("echo hi").unwrap(), None);
+    }
+
+    #[test]
+    fn selects_words() {
+        assert_eq!(expand("ls !$").unwrap().as_deref(), Some("ls src/main.rs"));
+        assert_eq!(expand("echo !^").unwrap().as_deref(), Some("echo -n"));
+        assert_eq!(expand("echo !*").unwrap().as_deref(), Some("echo -n todo src/main.rs"));
+        assert_eq!(expand("echo !-2:1-2").unwrap().as_deref(), Some("echo notes.txt backup/"));
+    }
+
+    #[test]
+    fn substitutes_the_previous_line() {
+        assert_eq!(expand("^todo^fixme").unwrap().as_deref(), Some("grep -n fixme src/main.rs"));
+        assert!(expand("^nothing^else").is_err());
+    }
+
+    #[test]
+    fn skips_quoted_and_blank_references() {
+        assert_eq!(expand("echo '!!'").unwrap(), None);
+        assert_eq!(expand("echo \\!!").unwrap(), None);
+        assert_eq!(expand("echo ! x").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_missing_events() {
+        assert!(expand("!0").is_err());
+        assert!(expand("!4").is_err());
+        assert!(expand("!-0").is_err());
+        assert!(expand("!-4").is_err());
+        assert!(expand("!-99999999999999999999999").is_err());
+        assert!(expand("!nothing").is_err());
+        assert!(expand_history("!!", &[]).is_err());
+    }
+}
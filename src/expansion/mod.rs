@@ -0,0 +1,8 @@
+pub(crate) mod arithmetic;
+pub(crate) mod brace;
+pub(crate) mod command;
+pub(crate) mod expand;
+pub(crate) mod glob;
+pub(crate) mod history;
+pub(crate) mod param;
+pub(crate) mod pattern;
\ No newline at end of file
@@ -0,0 +1,168 @@
+use crate::expansion::expand::{expand_pattern, expand_word};
+use crate::expansion::pattern::matches_chars;
+use crate::parser::lexer::{ParamOp, ReplaceMode};
+use crate::shell::Shell;
+use crate::utils::error::ShellError;
+use crate::utils::messages::{CANNOT_ASSIGN, PARAMETER_NOT_SET};
+use crate::variables::is_valid_name;
+
+/**
+ * Expand a parameter, applying the operation of a `${...}` expansion if any.
+ *
+ * `${name?message}` fails when the parameter is unset, and makes a shell that is not interactive
+ * exit with the status 1 after printing the message.
+ *
+ * # Arguments
+ * * `shell` - The shell holding the parameters.
+ * * `name` - The name of the parameter.
+ * * `op` - The operation to apply to the parameter.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::param::expand_param;
+ * use shell::parser::lexer::ParamOp;
+ * use shell::shell::Shell;
+ *
+ * let mut shell = Shell::new();
+ * shell.variables.set("FILE", "archive.tar.gz");
+ * let op = ParamOp::Length;
+ * assert_eq!(expand_param(&mut shell, "FILE", Some(&op)).unwrap(), "14");
+ * ```
+ */
+pub fn expand_param(shell: &mut Shell, name: &str, op: Option<&ParamOp>) -> Result<String, ShellError> {
+    let value = shell.param(name);
+    let Some(op) = op else {
+        return Ok(value.unwrap_or_default());
+    };
+    let is_unset = |colon: bool| match &value {
+        None => true,
+        Some(value) => colon && value.is_empty(),
+    };
+
+    match op {
+        ParamOp::Default { colon, word } => match is_unset(*colon) {
+            true => expand_word(shell, word),
+            false => Ok(value.unwrap_or_default()),
+        },
+        ParamOp::Assign { colon, word } => {
+            if !is_unset(*colon) {
+                return Ok(value.unwrap_or_default());
+            }
+            if !is_valid_name(name) {
+                return Err(ShellError::ExpansionError(format!("${}: {CANNOT_ASSIGN}", name)));
+            }
+            let value = expand_word(shell, word)?;
+            shell.variables.set(name, &value);
+            Ok(value)
+        }
+        ParamOp::Error { colon, word } => {
+            if !is_unset(*colon) {
+                return Ok(value.unwrap_or_default());
+            }
+            let message = expand_word(shell, word)?;
+            let message = if message.is_empty() { PARAMETER_NOT_SET.to_owned() } else { message };
+            // A script or a `-c` command stops once the error is reported, like after `exit 1`.
+            shell.exit_on_error = !shell.interactive;
+            Err(ShellError::ExpansionError(format!("{}: {}", name, message)))
+        }
+        ParamOp::Alternative { colon, word } => match is_unset(*colon) {
+            true => Ok(String::new()),
+            false => expand_word(shell, word),
+        },
+        ParamOp::Length => Ok(value.unwrap_or_default().chars().count().to_string()),
+        ParamOp::RemovePrefix { longest, pattern } => {
+            let pattern = expand_pattern(shell, pattern)?;
+            Ok(remove_prefix(&value.unwrap_or_default(), &pattern, *longest))
+        }
+        ParamOp::RemoveSuffix { longest, pattern } => {
+            let pattern = expand_pattern(shell, pattern)?;
+            Ok(remove_suffix(&value.unwrap_or_default(), &pattern, *longest))
+        }
+        ParamOp::Replace { mode, pattern, replacement } => {
+            let pattern = expand_pattern(shell, pattern)?;
+            let replacement = expand_word(shell, replacement)?;
+            Ok(replace(&value.unwrap_or_default(), &pattern, &replacement, *mode))
+        }
+    }
+}
+
+/**
+ * Remove the shortest or longest prefix of the value matching the pattern.
+ */
+fn remove_prefix(value: &str, pattern: &str, longest: bool) -> String {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = value.chars().collect();
+
+    let mut ends: Box<dyn Iterator<Item = usize>> = match longest {
+        true => Box::new((0..=chars.len()).rev()),
+        false => Box::new(0..=chars.len()),
+    };
+    match ends.find(|&end| matches_chars(&pattern, &chars[..end])) {
+        Some(end) => chars[end..].iter().collect(),
+        None => value.to_owned(),
+    }
+}
+
+/**
+ * Remove the shortest or longest suffix of the value matching the pattern.
+ */
+fn remove_suffix(value: &str, pattern: &str, longest: bool) -> String {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = value.chars().collect();
+
+    let mut starts: Box<dyn Iterator<Item = usize>> = match longest {
+        true => Box::new(0..=chars.len()),
+        false => Box::new((0..=chars.len()).rev()),
+    };
+    match starts.find(|&start| matches_chars(&pattern, &chars[start..])) {
+        Some(start) => chars[..start].iter().collect(),
+        None => value.to_owned(),
+    }
+}
+
+/**
+ * Replace the longest matches of the pattern in the value.
+ *
+ * An empty pattern never matches, so the value is returned unchanged.
+ */
+fn replace(value: &str, pattern: &str, replacement: &str, mode: ReplaceMode) -> String {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let chars: Vec<char> = value.chars().collect();
+    if pattern.is_empty() {
+        return value.to_owned();
+    }
+
+    let longest_match = |start: usize| (start + 1..=chars.len()).rev().find(|&end| matches_chars(&pattern, &chars[start..end]));
+
+    match mode {
+        ReplaceMode::Prefix => match longest_match(0) {
+            Some(end) => format!("{}{}", replacement, chars[end..].iter().collect::<String>()),
+            None => value.to_owned(),
+        },
+        ReplaceMode::Suffix => match (0..chars.len()).find(|&start| matches_chars(&pattern, &chars[start..])) {
+            Some(start) => format!("{}{}", chars[..start].iter().collect::<String>(), replacement),
+            None => value.to_owned(),
+        },
+        ReplaceMode::First | ReplaceMode::All => {
+            let mut result = String::with_capacity(value.len());
+            let mut start = 0;
+            while start < chars.len() {
+                match longest_match(start) {
+                    Some(end) => {
+                        result.push_str(replacement);
+                        start = end;
+                        if mode == ReplaceMode::First {
+                            break;
+                        }
+                    }
+                    None => {
+                        result.push(chars[start]);
+                        start += 1;
+                    }
+                }
+            }
+            result.extend(&chars[start..]);
+            result
+        }
+    }
+}
@@ -0,0 +1,213 @@
+/**
+ * Check whether a text matches a shell pattern.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::pattern::matches;
+ *
+ * assert!(!matches("[a-c]*.txt", "notes.txt"));
+ * assert!(matches("[a-c]*.txt", "build.txt"));
+ * ```
+ */
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches_chars(&pattern, &text)
+}
+
+/**
+ * Check whether a text matches a shell pattern, both given as characters.
+ *
+ * Patterns support `*` (any string), `?` (any character), bracket expressions such as `[a-z]`,
+ * `[!0-9]` or `[[:alpha:]]`, and backslash escapes for literal characters.
+ *
+ * `*` is handled by remembering the last star and retrying from the next character of the text
+ * when the rest of the pattern fails to match.
+ *
+ * # Arguments
+ * * `pattern` - The pattern.
+ * * `text` - The text to match.
+ *
+ * # Example
+ * ```rust
+ * use shell::expansion::pattern::matches_chars;
+ *
+ * let pattern: Vec<char> = "*.log".chars().collect();
+ * let text: Vec<char> = "build.log".chars().collect();
+ * assert!(matches_chars(&pattern, &text));
+ * ```
+ */
+pub fn matches_chars(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() {
+            match pattern[p] {
+                '*' => {
+                    star = Some((p, t));
+                    p += 1;
+                    continue;
+                }
+                '?' => {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                '[' => {
+                    if let Some((matched, end)) = match_bracket(pattern, p, text[t]) {
+                        if matched {
+                            p = end;
+                            t += 1;
+                            continue;
+                        }
+                    } else if text[t] == '[' {
+                        p += 1;
+                        t += 1;
+                        continue;
+                    }
+                }
+                '\\' if p + 1 < pattern.len() => {
+                    if pattern[p + 1] == text[t] {
+                        p += 2;
+                        t += 1;
+                        continue;
+                    }
+                }
+                c => {
+                    if c == text[t] {
+                        p += 1;
+                        t += 1;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        match star {
+            Some((star_p, star_t)) => {
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some((star_p, star_t + 1));
+            }
+            None => return false,
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/**
+ * Match a character against the bracket expression starting at `start`.
+ *
+ * Returns whether it matched and the position right after the closing bracket,
+ * or `None` if the bracket is not closed and must be taken literally.
+ */
+fn match_bracket(pattern: &[char], start: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let current = *pattern.get(i)?;
+        if current == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+
+        if current == '[' && pattern.get(i + 1) == Some(&':') {
+            let rest: String = pattern[i + 2..].iter().collect();
+            if let Some(end) = rest.find(":]") {
+                matched |= match_class(&rest[..end], c);
+                i += 2 + rest[..end].chars().count() + 2;
+                continue;
+            }
+        }
+
+        let (low, next) = if current == '\\' {
+            (*pattern.get(i + 1)?, i + 2)
+        } else {
+            (current, i + 1)
+        };
+
+        if pattern.get(next) == Some(&'-') && pattern.get(next + 1).is_some_and(|&high| high != ']') {
+            let high = pattern[next + 1];
+            matched |= low <= c && c <= high;
+            i = next + 2;
+        } else {
+            matched |= low == c;
+            i = next;
+        }
+    }
+}
+
+/**
+ * Match a character against a POSIX character class such as `alpha` or `digit`.
+ */
+fn match_class(class: &str, c: char) -> bool {
+    match class {
+        "alpha" => c.is_alphabetic(),
+        "digit" => c.is_ascii_digit(),
+        "alnum" => c.is_alphanumeric(),
+        "upper" => c.is_uppercase(),
+        "lower" => c.is_lowercase(),
+        "space" => c.is_whitespace(),
+        "blank" => c == ' ' || c == '\t',
+        "punct" => c.is_ascii_punctuation(),
+        "xdigit" => c.is_ascii_hexdigit(),
+        "cntrl" => c.is_control(),
+        "print" => !c.is_control(),
+        "graph" => !c.is_control() && !c.is_whitespace(),
+        _ => false,
+    }
+}
+
+/**
+ * Escape the special characters of a text so that it only matches itself in a pattern.
+ */
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/**
+ * Check whether a pattern has unescaped special characters, and so may match other texts than itself.
+ */
+pub fn has_wildcards(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '*' | '?' | '[' => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/**
+ * Remove the backslashes escaping the characters of a pattern.
+ */
+pub fn unescape(pattern: &str) -> String {
+    let mut text = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.extend(chars.next()),
+            c => text.push(c),
+        }
+    }
+    text
+}
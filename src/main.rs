@@ -1,44 +1,155 @@
-use crate::commands::exit::exit;
-use crate::shell::Shell;
-use crate::utils::messages::CANNOT_READ_INPUT;
-use std::io::{stdin, stdout, Write};
-use std::{env, io};
+use crate::editor::git::GitPrompt;
+use crate::editor::history::{history_path, History};
+use crate::editor::line::LineEditor;
+use crate::editor::prompt::{render_prompt, DEFAULT_PS1, DEFAULT_PS2};
+use crate::editor::terminal::{is_tty, read_raw_line, STDIN};
+use crate::exec::script::{run_file, run_script, run_startup_files};
+use crate::exec::stream::Streams;
+use crate::expansion::history::expand_history;
+use crate::parser::parse::{is_incomplete, join_lines};
+use crate::shell::{Shell, SHELL_NAME};
+use crate::utils::error::ShellError;
+use crate::utils::signal::{clear_interrupt, ignore_interrupts};
+use crate::utils::messages::{CANNOT_READ_INPUT, INVALID_OPTION, NO_SUCH_FILE, OPTION_REQUIRES_ARGUMENT};
+use std::path::Path;
+use std::{env, io, process};
 
 mod commands;
+mod editor;
+mod exec;
+mod expansion;
+mod parser;
 mod utils;
 mod shell;
+mod variables;
 
+/**
+ * Where the shell reads its commands from, according to its arguments.
+ */
+enum Source {
+    /// The terminal, or stdin when it is not a terminal.
+    Stdin,
+    /// The commands given with `-c`.
+    Command(String),
+    /// A script file, such as one starting with `#!/path/to/0-shell`.
+    Script(String),
+}
+
+/**
+ * Start the shell, as `0-shell [-l] [--norc] [-c command [name] | script] [args...]`.
+ *
+ * The arguments after the script, or after the name given with `-c`, are the positional parameters.
+ */
 fn main() -> io::Result<()> {
+    // A login shell is started with `-l`, or by a program putting a dash before its name.
+    let mut login = env::args().next().is_some_and(|name| name.starts_with('-'));
+    let mut norc = false;
+    let mut command = None;
+    let mut operands = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-l" | "--login" => login = true,
+            "--norc" => norc = true,
+            "-c" => match args.next() {
+                Some(text) => command = Some(text),
+                None => usage_error(&arg, OPTION_REQUIRES_ARGUMENT),
+            },
+            "--" => operands.extend(args.by_ref()),
+            _ if arg.starts_with('-') => usage_error(&arg, INVALID_OPTION),
+            // The options end at the script, whose own options are its arguments.
+            _ => {
+                operands.push(arg);
+                operands.extend(args.by_ref());
+            }
+        }
+    }
+
     let mut shell = Shell::new();
+    let mut operands = operands.into_iter();
+    let source = match (command, operands.next()) {
+        (Some(command), name) => {
+            shell.name = name.unwrap_or_else(|| SHELL_NAME.to_owned());
+            Source::Command(command)
+        }
+        (None, Some(path)) => {
+            shell.name = path.clone();
+            Source::Script(path)
+        }
+        (None, None) => Source::Stdin,
+    };
+    shell.positional = operands.collect();
 
-    loop {
-        let current_dir = env::current_dir()?;
-        let current_dir_display = current_dir.to_string_lossy();
-
-        let home_dir = env::var("HOME").unwrap_or_else(|_| "".to_string());
-        let prompt = if current_dir_display.starts_with(&home_dir) {
-            format!("~{}", &current_dir_display[home_dir.len()..])
-        } else {
-            current_dir_display.to_string()
-        };
+    let interactive = matches!(source, Source::Stdin) && is_tty(STDIN);
+    shell.interactive = interactive;
+    if interactive {
+        ignore_interrupts();
+    }
+    if !norc {
+        if let Err(ShellError::Exit(code)) = run_startup_files(&mut shell, login, interactive) {
+            process::exit(code);
+        }
+    }
+
+    let result = match source {
+        Source::Command(command) => shell.execute(&command),
+        Source::Script(path) => run_file(&mut shell, Path::new(&path), &Streams::inherit()).map_err(|e| match e {
+            ShellError::IoError(e) => {
+                let (message, status) = match e.kind() {
+                    io::ErrorKind::NotFound => (NO_SUCH_FILE.to_owned(), 127),
+                    _ => (e.to_string(), 126),
+                };
+                eprintln!("\x1b[31m{SHELL_NAME}: {}: {}\x1b[0m", path, message);
+                ShellError::Exit(status)
+            }
+            e => e,
+        }),
+        Source::Stdin if !interactive => run_script(&mut shell, SHELL_NAME, read_raw_line, &Streams::inherit()),
+        Source::Stdin => return run_interactive(shell),
+    };
+    match result {
+        Ok(_) => process::exit(shell.last_status),
+        Err(ShellError::Exit(code)) => process::exit(code),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(shell.last_status);
+        }
+    }
+}
 
-        print!("\x1b[1;33;48;5;238m{}\x1b[0m $ \x1b[38;5;238m\x1b[0m", prompt);
-        stdout().flush()?;
+/**
+ * Print an error about the options of the shell, and exit with the status 2.
+ */
+fn usage_error(option: &str, message: &str) -> ! {
+    eprintln!("\x1b[31m{SHELL_NAME}: {option}: {message}\x1b[0m");
+    process::exit(2);
+}
 
-        let mut input = String::new();
-        match stdin().read_line(&mut input) {
-            Ok(0) => {
+/**
+ * Read and run commands from the terminal, with the line editor and the history, until the end of the input.
+ */
+fn run_interactive(mut shell: Shell) -> io::Result<()> {
+    let mut editor = LineEditor::new();
+    let mut git = GitPrompt::default();
+    // Loaded after the startup files, which may set `HISTFILE` and `HISTSIZE`.
+    if let Some(path) = history_path(&shell.variables) {
+        shell.history = History::load(path, &shell.variables);
+    }
+
+    loop {
+        match read_command(&mut editor, &mut git, &mut shell) {
+            Ok(None) => {
                 println!();
-                exit();
+                process::exit(shell.last_status);
             }
-            Ok(_) => {
+            Ok(Some(input)) => {
                 let input = input.trim();
                 if !input.is_empty() {
-                    if input == "exit" {
-                        exit();
-                    }
+                    clear_interrupt();
                     match shell.execute(input) {
                         Ok(_) => (),
+                        Err(ShellError::Exit(code)) => process::exit(code),
+                        Err(e @ ShellError::Interrupted) => shell.last_status = e.status(),
                         Err(e) => eprintln!("{}", e),
                     }
                 }
@@ -51,4 +162,55 @@ fn main() -> io::Result<()> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/**
+ * Read a command, with the `PS1` prompt and then `PS2` continuation lines while it is incomplete.
+ *
+ * The history references of every line are expanded, and the whole command is added to the history
+ * on a single line.
+ *
+ * Returns `None` at the end of the input, and an empty command when it is cancelled with `Ctrl+C`
+ * or a history reference fails.
+ */
+fn read_command(editor: &mut LineEditor, git: &mut GitPrompt, shell: &mut Shell) -> io::Result<Option<String>> {
+    let mut input = String::new();
+    for number in 0.. {
+        let (name, default) = if number == 0 { ("PS1", DEFAULT_PS1) } else { ("PS2", DEFAULT_PS2) };
+        let prompt = render_prompt(shell.variables.get(name).unwrap_or(default), shell, git);
+        let line = match editor.read_line(&prompt, shell) {
+            Ok(Some(line)) => line,
+            // An incomplete command at the end of the input still runs, to report its error.
+            Ok(None) if number == 0 => return Ok(None),
+            Ok(None) => break,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                shell.last_status = 130;
+                return Ok(Some(String::new()));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let line = match expand_history(&line, shell.history.entries()) {
+            Ok(Some(expanded)) => {
+                println!("{}", expanded);
+                expanded
+            }
+            Ok(None) => line,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(Some(String::new()));
+            }
+        };
+
+        if number > 0 {
+            input.push('\n');
+        }
+        input.push_str(&line);
+        if !is_incomplete(&input) {
+            break;
+        }
+    }
+
+    shell.history.add(&join_lines(&input), &shell.variables);
+    Ok(Some(input))
+}
@@ -0,0 +1,156 @@
+use crate::parser::lexer::{tokenize, Token, Word, WordPart};
+use crate::parser::parse::OPENING_WORDS;
+use crate::utils::error::ShellError;
+use std::collections::BTreeMap;
+
+/**
+ * Replace the aliases used as command names by their values.
+ *
+ * The value of an alias is split into tokens, so it may hold several commands or a pipeline,
+ * and its first word is expanded again. An alias is not expanded inside its own value, so that
+ * `alias ls='ls -F'` does not loop. When a value ends with a blank, the word following the alias
+ * is expanded too, as in `alias sudo='sudo '`.
+ *
+ * Only unquoted words are expanded: `\ls` or `'ls'` runs the command itself. The patterns of a `case`
+ * command are not command names, so they are not expanded either.
+ *
+ * # Arguments
+ * * `tokens` - The tokens of a command line.
+ * * `aliases` - The aliases of the shell.
+ *
+ * # Example
+ * ```rust
+ * use std::collections::BTreeMap;
+ * use shell::parser::alias::expand_aliases;
+ * use shell::parser::lexer::tokenize;
+ *
+ * let aliases = BTreeMap::from([("ll".to_owned(), "ls -l".to_owned())]);
+ * let tokens = expand_aliases(tokenize("ll src").unwrap(), &aliases).unwrap();
+ * assert_eq!(tokens.len(), 3);
+ * ```
+ */
+pub fn expand_aliases(tokens: Vec<Token>, aliases: &BTreeMap<String, String>) -> Result<Vec<Token>, ShellError> {
+    if aliases.is_empty() {
+        return Ok(tokens);
+    }
+    expand(tokens, aliases, &mut Vec::new(), true).map(|(tokens, _)| tokens)
+}
+
+/**
+ * Where the tokens of a `case` command are, as its patterns are not command names.
+ */
+#[derive(PartialEq)]
+enum CasePart {
+    /// Outside of any `case`, or in the commands of one of its items.
+    Commands,
+    /// After `case`, before the word matched against the patterns.
+    Word,
+    /// After the word, before `in`.
+    In,
+    /// In the patterns of an item, after `in` or `;;` and before `)`.
+    Patterns,
+}
+
+/**
+ * Expand the aliases of a list of tokens, the first word being a command name if `check_first` is set.
+ *
+ * Returns the expanded tokens and whether the word following them should be expanded too.
+ */
+fn expand(
+    tokens: Vec<Token>,
+    aliases: &BTreeMap<String, String>,
+    active: &mut Vec<String>,
+    check_first: bool,
+) -> Result<(Vec<Token>, bool), ShellError> {
+    let mut expanded = Vec::with_capacity(tokens.len());
+    let mut check = check_first;
+    let mut redirect_target = false;
+    let mut case = CasePart::Commands;
+
+    for token in tokens {
+        match token {
+            Token::Word(word) if redirect_target => {
+                redirect_target = false;
+                expanded.push(Token::Word(word));
+            }
+            Token::Word(word) if case != CasePart::Commands => {
+                let name = alias_name(&word);
+                case = match case {
+                    CasePart::Word => CasePart::In,
+                    CasePart::In if name.as_deref() == Some("in") => CasePart::Patterns,
+                    CasePart::Patterns if name.as_deref() != Some("esac") => CasePart::Patterns,
+                    _ => CasePart::Commands,
+                };
+                check = false;
+                expanded.push(Token::Word(word));
+            }
+            Token::Word(word) if check => {
+                let value = alias_name(&word)
+                    .filter(|name| !active.contains(name))
+                    .and_then(|name| Some((name.clone(), aliases.get(&name)?)));
+                match value {
+                    Some((name, value)) => {
+                        active.push(name);
+                        let result = tokenize(value).and_then(|tokens| expand(tokens, aliases, active, true));
+                        active.pop();
+                        let (tokens, check_next) = result?;
+                        expanded.extend(tokens);
+                        check = check_next || value.ends_with([' ', '\t']);
+                    }
+                    None => {
+                        // Assignments before the command name and reserved words such as `then`
+                        // keep the next word a command name.
+                        let name = alias_name(&word);
+                        if name.as_deref() == Some("case") {
+                            case = CasePart::Word;
+                        }
+                        check = word.assignment().is_some()
+                            || name.is_some_and(|name| OPENING_WORDS.contains(&name.as_str()));
+                        expanded.push(Token::Word(word));
+                    }
+                }
+            }
+            // The alternatives of a pattern, or the lines before it.
+            Token::Pipe | Token::Newline if case == CasePart::Patterns => expanded.push(token),
+            Token::DoubleSemicolon => {
+                case = CasePart::Patterns;
+                check = false;
+                expanded.push(token);
+            }
+            Token::RightParen if case == CasePart::Patterns => {
+                case = CasePart::Commands;
+                check = true;
+                expanded.push(token);
+            }
+            Token::Redirect(..) => {
+                redirect_target = true;
+                expanded.push(token);
+            }
+            Token::Semicolon
+            | Token::Newline
+            | Token::Pipe
+            | Token::And
+            | Token::Or
+            | Token::RightParen => {
+                check = true;
+                expanded.push(token);
+            }
+            token => {
+                check = false;
+                expanded.push(token);
+            }
+        }
+    }
+
+    Ok((expanded, check))
+}
+
+/**
+ * Get the text of a word that is written without any quote or expansion.
+ */
+fn alias_name(word: &Word) -> Option<String> {
+    match word.parts.as_slice() {
+        [WordPart::Literal(name)] => Some(name.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,129 @@
+use crate::parser::lexer::{RedirectKind, Word};
+use std::sync::Arc;
+
+/**
+ * A redirection of one of the standard streams of a command, e.g. `2> errors.log`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redirect {
+    pub fd: u32,
+    pub kind: RedirectKind,
+    pub target: Word,
+}
+
+/**
+ * A variable assignment written before a command, e.g. `FOO=1` in `FOO=1 make`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub name: String,
+    pub value: Word,
+}
+
+/**
+ * A single command with its assignments, arguments and redirections, e.g. `LANG=C ls -l > listing.txt`.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SimpleCommand {
+    pub assignments: Vec<Assignment>,
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+}
+
+impl SimpleCommand {
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty() && self.words.is_empty() && self.redirects.is_empty()
+    }
+}
+
+/**
+ * A command of a pipeline.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Simple(SimpleCommand),
+    /// A compound command, with redirections applying to every command it runs.
+    Compound { body: CompoundCommand, redirects: Vec<Redirect> },
+    /// `name() { list; }`, defining a function. The body is shared with the functions of the shell.
+    Function { name: String, body: Arc<Command> },
+}
+
+/**
+ * A command made of other commands, run in the current shell.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompoundCommand {
+    /// `{ list; }`
+    Group(CommandList),
+    /// `if list; then list; [elif list; then list;]... [else list;] fi`
+    If {
+        branches: Vec<(CommandList, CommandList)>,
+        otherwise: Option<CommandList>,
+    },
+    /// `while list; do list; done`, or `until list; do list; done` which loops while the condition fails.
+    While {
+        condition: CommandList,
+        body: CommandList,
+        until: bool,
+    },
+    /// `for name [in word...]; do list; done`, over the positional parameters without `in`.
+    For {
+        name: String,
+        words: Option<Vec<Word>>,
+        body: CommandList,
+    },
+    /// `for ((init; condition; step)); do list; done`, where an empty condition is true.
+    ArithmeticFor {
+        init: Word,
+        condition: Word,
+        step: Word,
+        body: CommandList,
+    },
+    /// `case word in [(]pattern[|pattern]...) list;; ... esac`
+    Case { word: Word, items: Vec<CaseItem> },
+}
+
+/**
+ * The patterns of a `case` command and the commands run when one of them matches.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseItem {
+    pub patterns: Vec<Word>,
+    pub body: CommandList,
+}
+
+/**
+ * Commands connected with `|`, the output of each one feeding the next one.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+}
+
+/**
+ * The operator between two pipelines of an and-or list.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Connector {
+    /// `&&`: run the next pipeline only if the previous one succeeded.
+    And,
+    /// `||`: run the next pipeline only if the previous one failed.
+    Or,
+}
+
+/**
+ * Pipelines chained with `&&` and `||`, e.g. `mkdir x && cd x`.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AndOrList {
+    pub first: Pipeline,
+    pub rest: Vec<(Connector, Pipeline)>,
+}
+
+/**
+ * And-or lists separated by `;` or newlines, run one after the other.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CommandList {
+    pub items: Vec<AndOrList>,
+}
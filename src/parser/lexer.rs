@@ -0,0 +1,803 @@
+use crate::utils::error::ShellError;
+use crate::utils::messages::{BAD_SUBSTITUTION, UNEXPECTED_EOF};
+use crate::variables::is_valid_name;
+use std::fmt::{Display, Formatter};
+
+/**
+ * A piece of a word.
+ *
+ * Quoted parts keep track of the fact that they were quoted so that later stages
+ * (globbing, field splitting...) can leave them untouched.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordPart {
+    Literal(String),
+    Quoted(String),
+    Param { name: String, op: Option<Box<ParamOp>>, quoted: bool },
+    /// `$(command)` or `` `command` ``, keeping the source of the command to run.
+    Command { source: String, quoted: bool },
+    /// `$((expression))`, the expression being expanded before it is evaluated.
+    Arithmetic { expression: Word, quoted: bool },
+}
+
+/**
+ * The operation of a `${...}` parameter expansion.
+ *
+ * The `colon` flag of the first four operations makes them treat an empty value like an unset one.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamOp {
+    /// `${name:-word}`: use `word` if the parameter is unset.
+    Default { colon: bool, word: Word },
+    /// `${name:=word}`: assign `word` to the variable if it is unset.
+    Assign { colon: bool, word: Word },
+    /// `${name:?word}`: fail with `word` as the message if the parameter is unset.
+    Error { colon: bool, word: Word },
+    /// `${name:+word}`: use `word` only if the parameter is set.
+    Alternative { colon: bool, word: Word },
+    /// `${#name}`: the length of the value.
+    Length,
+    /// `${name#pattern}` and `${name##pattern}`.
+    RemovePrefix { longest: bool, pattern: Word },
+    /// `${name%pattern}` and `${name%%pattern}`.
+    RemoveSuffix { longest: bool, pattern: Word },
+    /// `${name/pattern/replacement}` and its `//`, `/#` and `/%` variants.
+    Replace { mode: ReplaceMode, pattern: Word, replacement: Word },
+}
+
+/**
+ * Which matches of the pattern a `${name/pattern/replacement}` expansion replaces.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaceMode {
+    First,
+    All,
+    Prefix,
+    Suffix,
+}
+
+/**
+ * A shell word, made of one or more adjacent parts.
+ *
+ * `"my "file'.txt'` is a single word made of three parts.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+}
+
+impl Word {
+    /**
+     * Get the text of the word with all quotes removed, leaving expansions as they were written.
+     */
+    pub fn text(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                WordPart::Literal(s) | WordPart::Quoted(s) => s.clone(),
+                WordPart::Param { name, op: None, .. } => format!("${}", name),
+                WordPart::Param { name, .. } => format!("${{{}}}", name),
+                WordPart::Command { source, .. } => format!("$({})", source),
+                WordPart::Arithmetic { expression, .. } => format!("$(({}))", expression.text()),
+            })
+            .collect()
+    }
+
+    /**
+     * Check whether the word contains a command substitution.
+     */
+    pub fn has_substitution(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part, WordPart::Command { .. }))
+    }
+
+    /**
+     * Split an assignment word such as `NAME=value` into the name and the value.
+     *
+     * The name must be unquoted and valid, the value may be made of any parts.
+     */
+    pub fn assignment(&self) -> Option<(String, Word)> {
+        let (first, rest) = self.parts.split_first()?;
+        let WordPart::Literal(text) = first else {
+            return None;
+        };
+        let (name, value) = text.split_once('=')?;
+        if !is_valid_name(name) {
+            return None;
+        }
+
+        let mut parts = Vec::with_capacity(self.parts.len());
+        if !value.is_empty() {
+            parts.push(WordPart::Literal(value.to_owned()));
+        }
+        parts.extend(rest.iter().cloned());
+
+        Some((name.to_owned(), Word { parts }))
+    }
+
+    fn push_literal(&mut self, c: char) {
+        match self.parts.last_mut() {
+            Some(WordPart::Literal(s)) => s.push(c),
+            _ => self.parts.push(WordPart::Literal(c.to_string())),
+        }
+    }
+
+    fn push_quoted(&mut self, c: char) {
+        match self.parts.last_mut() {
+            Some(WordPart::Quoted(s)) => s.push(c),
+            _ => self.parts.push(WordPart::Quoted(c.to_string())),
+        }
+    }
+}
+
+/**
+ * The kind of a redirection operator.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedirectKind {
+    /// `<`
+    Input,
+    /// `>`
+    Output,
+    /// `>>`
+    Append,
+    /// `>&`
+    Duplicate,
+    /// `&>`
+    OutputAll,
+    /// `&>>`
+    AppendAll,
+}
+
+impl RedirectKind {
+    fn symbol(&self) -> &'static str {
+        match self {
+            RedirectKind::Input => "<",
+            RedirectKind::Output => ">",
+            RedirectKind::Append => ">>",
+            RedirectKind::Duplicate => ">&",
+            RedirectKind::OutputAll => "&>",
+            RedirectKind::AppendAll => "&>>",
+        }
+    }
+}
+
+/**
+ * A token produced by the lexer: either a word or an operator.
+ *
+ * Redirections carry the file descriptor written before them, as in `2>`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Word(Word),
+    Pipe,
+    And,
+    Or,
+    Semicolon,
+    /// `;;`, ending an item of a `case` command.
+    DoubleSemicolon,
+    Newline,
+    Redirect(Option<u32>, RedirectKind),
+    /// `(`, as in `name()`.
+    LeftParen,
+    /// `)`
+    RightParen,
+    /// `((expression))` at the start of a command.
+    Arithmetic(Word),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word.text()),
+            Token::Pipe => write!(f, "|"),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
+            Token::Semicolon => write!(f, ";"),
+            Token::DoubleSemicolon => write!(f, ";;"),
+            Token::Newline => write!(f, "newline"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Redirect(Some(fd), kind) => write!(f, "{}{}", fd, kind.symbol()),
+            Token::Redirect(None, kind) => write!(f, "{}", kind.symbol()),
+            Token::Arithmetic(word) => write!(f, "(({}))", word.text()),
+        }
+    }
+}
+
+/**
+ * The lexer turns command lines into a list of tokens.
+ *
+ * It understands single quotes, double quotes, backslash escapes, comments and operators.
+ */
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn new(input: &str) -> Self {
+        Lexer {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    /**
+     * Check whether an operator starts at the current position and therefore ends an unquoted word.
+     */
+    fn at_operator(&self) -> bool {
+        match self.peek() {
+            Some('|' | '<' | '>' | ';' | '\n' | '(' | ')') => true,
+            Some('&') => matches!(self.peek_at(1), Some('>' | '&')),
+            _ => false,
+        }
+    }
+
+    /**
+     * Read the operator starting at the current position.
+     */
+    fn read_operator(&mut self, fd: Option<u32>) -> Token {
+        let (token, len) = match (self.peek(), self.peek_at(1), self.peek_at(2)) {
+            (Some('|'), Some('|'), _) => (Token::Or, 2),
+            (Some('|'), _, _) => (Token::Pipe, 1),
+            (Some('&'), Some('&'), _) => (Token::And, 2),
+            (Some(';'), Some(';'), _) => (Token::DoubleSemicolon, 2),
+            (Some(';'), _, _) => (Token::Semicolon, 1),
+            (Some('\n'), _, _) => (Token::Newline, 1),
+            (Some('('), _, _) => (Token::LeftParen, 1),
+            (Some(')'), _, _) => (Token::RightParen, 1),
+            (Some('<'), _, _) => (Token::Redirect(fd, RedirectKind::Input), 1),
+            (Some('>'), Some('>'), _) => (Token::Redirect(fd, RedirectKind::Append), 2),
+            (Some('>'), Some('&'), _) => (Token::Redirect(fd, RedirectKind::Duplicate), 2),
+            (Some('>'), _, _) => (Token::Redirect(fd, RedirectKind::Output), 1),
+            (Some('&'), Some('>'), Some('>')) => (Token::Redirect(None, RedirectKind::AppendAll), 3),
+            _ => (Token::Redirect(None, RedirectKind::OutputAll), 2),
+        };
+
+        self.pos += len;
+        token
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() && c != '\n' {
+                self.pos += 1;
+            } else if c == '\\' && self.chars.get(self.pos + 1) == Some(&'\n') {
+                self.pos += 2;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    /**
+     * Read a full word starting at the current position.
+     */
+    fn read_word(&mut self) -> Result<Word, ShellError> {
+        let mut word = Word::default();
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || self.at_operator() {
+                break;
+            }
+            self.pos += 1;
+
+            match c {
+                '\\' => match self.next() {
+                    Some('\n') => {}
+                    Some(escaped) => word.push_quoted(escaped),
+                    None => word.push_literal('\\'),
+                },
+                '\'' => self.read_single_quoted(&mut word)?,
+                '"' => self.read_double_quoted(&mut word)?,
+                '`' => word.parts.push(self.read_backquoted(false)?),
+                '$' if self.read_dollar(&mut word, false)? => {}
+                _ => word.push_literal(c),
+            }
+        }
+
+        Ok(word)
+    }
+
+    /**
+     * Everything up to the closing quote is taken literally.
+     */
+    fn read_single_quoted(&mut self, word: &mut Word) -> Result<(), ShellError> {
+        word.parts.push(WordPart::Quoted(String::new()));
+        loop {
+            match self.next() {
+                Some('\'') => return Ok(()),
+                Some(c) => word.push_quoted(c),
+                None => return Err(unterminated('\'')),
+            }
+        }
+    }
+
+    /**
+     * Inside double quotes a backslash only escapes `$`, `` ` ``, `"`, `\` and newlines.
+     */
+    fn read_double_quoted(&mut self, word: &mut Word) -> Result<(), ShellError> {
+        word.parts.push(WordPart::Quoted(String::new()));
+        loop {
+            match self.next() {
+                Some('"') => return Ok(()),
+                Some('\\') => match self.next() {
+                    Some('\n') => {}
+                    Some(c @ ('$' | '`' | '"' | '\\')) => word.push_quoted(c),
+                    Some(c) => {
+                        word.push_quoted('\\');
+                        word.push_quoted(c);
+                    }
+                    None => return Err(unterminated('"')),
+                },
+                Some('`') => word.parts.push(self.read_backquoted(true)?),
+                Some('$') if self.read_dollar(word, true)? => {}
+                Some(c) => word.push_quoted(c),
+                None => return Err(unterminated('"')),
+            }
+        }
+    }
+
+    /**
+     * Read the expansion following a `$`, if any: `$?`, `$$`, `$0`, `$NAME`, `${...}`, `$(...)` or `$((...))`.
+     *
+     * Returns `false` when the `$` does not start an expansion and must be kept as is.
+     */
+    fn read_dollar(&mut self, word: &mut Word, quoted: bool) -> Result<bool, ShellError> {
+        if self.peek() == Some('{') {
+            self.pos += 1;
+            word.parts.push(self.read_braced_param(quoted)?);
+            return Ok(true);
+        }
+        if self.peek() == Some('(') && self.peek_at(1) == Some('(') {
+            self.pos += 2;
+            let expression = self.read_arithmetic()?;
+            word.parts.push(WordPart::Arithmetic { expression, quoted });
+            return Ok(true);
+        }
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            let source = self.read_command_source()?;
+            word.parts.push(WordPart::Command { source, quoted });
+            return Ok(true);
+        }
+
+        match self.read_param_name(false) {
+            Some(name) => {
+                word.parts.push(WordPart::Param { name, op: None, quoted });
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /**
+     * Read the command of a `$(...)` substitution, the opening parenthesis being already consumed.
+     *
+     * Parentheses are counted so that nested substitutions and subshells are kept whole,
+     * except when they appear inside quotes.
+     */
+    fn read_command_source(&mut self) -> Result<String, ShellError> {
+        let start = self.pos;
+        let mut depth = 0;
+
+        loop {
+            match self.next().ok_or_else(|| unterminated(')'))? {
+                '\\' => {
+                    self.next();
+                }
+                '\'' => {
+                    while self.next().ok_or_else(|| unterminated('\''))? != '\'' {}
+                }
+                '"' => loop {
+                    match self.next().ok_or_else(|| unterminated('"'))? {
+                        '"' => break,
+                        '\\' => {
+                            self.next();
+                        }
+                        _ => {}
+                    }
+                },
+                '(' => depth += 1,
+                ')' if depth == 0 => break,
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Ok(self.chars[start..self.pos - 1].iter().collect())
+    }
+
+    /**
+     * Read an arithmetic expression up to the closing `))`, the opening parentheses being already consumed.
+     *
+     * The expression behaves as if it were double-quoted: parameters and commands are expanded,
+     * but nothing is split.
+     */
+    fn read_arithmetic(&mut self) -> Result<Word, ShellError> {
+        let mut word = Word::default();
+        let mut depth = 0;
+
+        loop {
+            match self.next().ok_or_else(|| unterminated(')'))? {
+                ')' if depth == 0 && self.peek() == Some(')') => {
+                    self.pos += 1;
+                    return Ok(word);
+                }
+                '\\' => match self.next() {
+                    Some(c) => word.push_quoted(c),
+                    None => return Err(unterminated(')')),
+                },
+                '"' => self.read_double_quoted(&mut word)?,
+                '`' => word.parts.push(self.read_backquoted(true)?),
+                '$' if self.read_dollar(&mut word, true)? => {}
+                c => {
+                    if c == '(' {
+                        depth += 1;
+                    } else if c == ')' {
+                        depth -= 1;
+                    }
+                    word.push_quoted(c);
+                }
+            }
+        }
+    }
+
+    /**
+     * Read a `` `command` `` substitution, the opening backquote being already consumed.
+     *
+     * A backslash only escapes `` ` ``, `$` and `\\`, and also `"` inside double quotes.
+     */
+    fn read_backquoted(&mut self, quoted: bool) -> Result<WordPart, ShellError> {
+        let mut source = String::new();
+
+        loop {
+            match self.next().ok_or_else(|| unterminated('`'))? {
+                '`' => break,
+                '\\' => match self.next() {
+                    Some(c @ ('`' | '$' | '\\')) => source.push(c),
+                    Some('"') if quoted => source.push('"'),
+                    Some(c) => {
+                        source.push('\\');
+                        source.push(c);
+                    }
+                    None => return Err(unterminated('`')),
+                },
+                c => source.push(c),
+            }
+        }
+
+        Ok(WordPart::Command { source, quoted })
+    }
+
+    /**
+     * Read the name of a parameter: a special character, digits or a variable name.
+     *
+     * Outside of braces only a single digit is part of the name, so `$10` is `$1` followed by `0`.
+     */
+    fn read_param_name(&mut self, braced: bool) -> Option<String> {
+        let mut name = String::new();
+        match self.peek()? {
+            c @ ('?' | '$' | '#' | '@' | '*') => {
+                self.pos += 1;
+                name.push(c);
+            }
+            c if c.is_ascii_digit() => {
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+                    name.push(c);
+                    self.pos += 1;
+                    if !braced {
+                        break;
+                    }
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                    self.pos += 1;
+                }
+            }
+            _ => return None,
+        }
+        Some(name)
+    }
+
+    /**
+     * Read a `${...}` expansion, the opening brace being already consumed.
+     */
+    fn read_braced_param(&mut self, quoted: bool) -> Result<WordPart, ShellError> {
+        let start = self.pos;
+
+        if self.peek() == Some('#') && self.peek_at(1) != Some('}') {
+            self.pos += 1;
+            if let Some(name) = self.read_param_name(true) {
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(WordPart::Param { name, op: Some(Box::new(ParamOp::Length)), quoted });
+                }
+            }
+            self.pos = start;
+        }
+
+        let Some(name) = self.read_param_name(true) else {
+            return Err(self.bad_substitution(start));
+        };
+
+        let op = match (self.peek(), self.peek_at(1)) {
+            (Some('}'), _) => {
+                self.pos += 1;
+                None
+            }
+            (Some(':'), Some(c @ ('-' | '=' | '?' | '+'))) => {
+                self.pos += 2;
+                Some(self.read_default_op(c, true, quoted)?)
+            }
+            (Some(c @ ('-' | '=' | '?' | '+')), _) => {
+                self.pos += 1;
+                Some(self.read_default_op(c, false, quoted)?)
+            }
+            (Some(c @ ('#' | '%')), next) => {
+                let longest = next == Some(c);
+                self.pos += if longest { 2 } else { 1 };
+                let (pattern, _) = self.read_param_word(&['}'], false)?;
+                Some(if c == '#' {
+                    ParamOp::RemovePrefix { longest, pattern }
+                } else {
+                    ParamOp::RemoveSuffix { longest, pattern }
+                })
+            }
+            (Some('/'), next) => {
+                self.pos += 1;
+                let mode = match next {
+                    Some('/') => ReplaceMode::All,
+                    Some('#') => ReplaceMode::Prefix,
+                    Some('%') => ReplaceMode::Suffix,
+                    _ => ReplaceMode::First,
+                };
+                if mode != ReplaceMode::First {
+                    self.pos += 1;
+                }
+                let (pattern, stop) = self.read_param_word(&['/', '}'], false)?;
+                let replacement = match stop {
+                    '/' => self.read_param_word(&['}'], quoted)?.0,
+                    _ => Word::default(),
+                };
+                Some(ParamOp::Replace { mode, pattern, replacement })
+            }
+            _ => return Err(self.bad_substitution(start)),
+        };
+
+        Ok(WordPart::Param { name, op: op.map(Box::new), quoted })
+    }
+
+    fn read_default_op(&mut self, c: char, colon: bool, quoted: bool) -> Result<ParamOp, ShellError> {
+        let (word, _) = self.read_param_word(&['}'], quoted)?;
+        Ok(match c {
+            '-' => ParamOp::Default { colon, word },
+            '=' => ParamOp::Assign { colon, word },
+            '?' => ParamOp::Error { colon, word },
+            _ => ParamOp::Alternative { colon, word },
+        })
+    }
+
+    /**
+     * Read the word inside a `${...}` expansion, up to one of the `stops` characters.
+     *
+     * Patterns are always read as unquoted, even inside double quotes, so that they keep
+     * their special characters.
+     *
+     * Returns the word and the character that ended it.
+     */
+    fn read_param_word(&mut self, stops: &[char], quoted: bool) -> Result<(Word, char), ShellError> {
+        let mut word = Word::default();
+        let mut depth = 0;
+
+        loop {
+            let c = self.next().ok_or_else(|| unterminated('}'))?;
+            match c {
+                c if depth == 0 && stops.contains(&c) => return Ok((word, c)),
+                '\\' => match self.next() {
+                    Some(escaped) => word.push_quoted(escaped),
+                    None => return Err(unterminated('}')),
+                },
+                '\'' if !quoted => self.read_single_quoted(&mut word)?,
+                '"' => self.read_double_quoted(&mut word)?,
+                '$' if self.read_dollar(&mut word, quoted)? => {}
+                c => {
+                    if c == '{' {
+                        depth += 1;
+                    } else if c == '}' {
+                        depth -= 1;
+                    }
+                    if quoted {
+                        word.push_quoted(c);
+                    } else {
+                        word.push_literal(c);
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Build a "bad substitution" error showing the whole `${...}` expansion.
+     */
+    fn bad_substitution(&mut self, start: usize) -> ShellError {
+        while let Some(c) = self.next() {
+            if c == '}' {
+                break;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        ShellError::SyntaxError(format!("${{{}: {BAD_SUBSTITUTION}", text))
+    }
+}
+
+/**
+ * Get the file descriptor written right before a redirection, as the `2` in `2>`.
+ */
+fn io_number(word: &Word) -> Option<u32> {
+    match word.parts.as_slice() {
+        [WordPart::Literal(s)] if s.chars().all(|c| c.is_ascii_digit()) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn unterminated(quote: char) -> ShellError {
+    ShellError::Incomplete(format!("{UNEXPECTED_EOF} `{}'", quote))
+}
+
+/**
+ * Split a command line into tokens, handling quotes and escapes.
+ *
+ * # Arguments
+ * * `input` - The command line to split.
+ *
+ * # Example
+ * ```rust
+ * use shell::parser::lexer::tokenize;
+ *
+ * let tokens = tokenize("touch \"my file.txt\" other").unwrap();
+ * assert_eq!(tokens.len(), 3);
+ * ```
+ */
+pub fn tokenize(input: &str) -> Result<Vec<Token>, ShellError> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    loop {
+        lexer.skip_blanks();
+        match lexer.peek() {
+            None => break,
+            Some('#') => lexer.skip_comment(),
+            Some('(') if lexer.peek_at(1) == Some('(') => {
+                lexer.pos += 2;
+                tokens.push(Token::Arithmetic(lexer.read_arithmetic()?));
+            }
+            Some(_) if lexer.at_operator() => tokens.push(lexer.read_operator(None)),
+            Some(_) => {
+                let word = lexer.read_word()?;
+                match io_number(&word) {
+                    Some(fd) if matches!(lexer.peek(), Some('<' | '>')) => {
+                        tokens.push(lexer.read_operator(Some(fd)));
+                    }
+                    _ => tokens.push(Token::Word(word)),
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(text: &str) -> Token {
+        Token::Word(Word { parts: vec![WordPart::Literal(text.to_owned())] })
+    }
+
+    #[test]
+    fn splits_words_and_operators() {
+        let tokens = tokenize("ls -l | wc -l && echo ok; echo done").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                literal("ls"),
+                literal("-l"),
+                Token::Pipe,
+                literal("wc"),
+                literal("-l"),
+                Token::And,
+                literal("echo"),
+                literal("ok"),
+                Token::Semicolon,
+                literal("echo"),
+                literal("done"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_parts_in_one_word() {
+        let tokens = tokenize("echo \"my \"file'.txt'").unwrap();
+        let Token::Word(word) = &tokens[1] else {
+            panic!("expected a word");
+        };
+        assert_eq!(
+            word.parts,
+            vec![
+                WordPart::Quoted("my ".to_owned()),
+                WordPart::Literal("file".to_owned()),
+                WordPart::Quoted(".txt".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_redirections_with_descriptors() {
+        let tokens = tokenize("cmd 2>&1 >> log").unwrap();
+        assert_eq!(tokens[1], Token::Redirect(Some(2), RedirectKind::Duplicate));
+        assert_eq!(tokens[2], literal("1"));
+        assert_eq!(tokens[3], Token::Redirect(None, RedirectKind::Append));
+    }
+
+    #[test]
+    fn reads_parameter_operations() {
+        let tokens = tokenize("echo ${name:-default} ${#name}").unwrap();
+        let Token::Word(word) = &tokens[1] else {
+            panic!("expected a word");
+        };
+        let WordPart::Param { name, op: Some(op), quoted: false } = &word.parts[0] else {
+            panic!("expected a parameter");
+        };
+        assert_eq!(name, "name");
+        assert!(matches!(**op, ParamOp::Default { colon: true, .. }));
+
+        let Token::Word(word) = &tokens[2] else {
+            panic!("expected a word");
+        };
+        assert!(matches!(&word.parts[0], WordPart::Param { op: Some(op), .. } if **op == ParamOp::Length));
+    }
+
+    #[test]
+    fn reports_unterminated_quotes_as_incomplete() {
+        assert!(matches!(tokenize("echo 'abc"), Err(ShellError::Incomplete(_))));
+        assert!(matches!(tokenize("echo \"abc"), Err(ShellError::Incomplete(_))));
+    }
+
+    #[test]
+    fn splits_assignments() {
+        let Token::Word(word) = &tokenize("PATH=/bin:$HOME").unwrap()[0] else {
+            panic!("expected a word");
+        };
+        let (name, value) = word.assignment().unwrap();
+        assert_eq!(name, "PATH");
+        assert_eq!(value.text(), "/bin:$HOME");
+        assert_eq!(Word { parts: vec![WordPart::Quoted("A=1".to_owned())] }.assignment(), None);
+    }
+}
@@ -0,0 +1,4 @@
+pub(crate) mod alias;
+pub(crate) mod ast;
+pub(crate) mod lexer;
+pub(crate) mod parse;
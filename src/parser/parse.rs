@@ -0,0 +1,735 @@
+use crate::parser::ast::{
+    AndOrList, Assignment, CaseItem, Command, CommandList, CompoundCommand, Connector, Pipeline, Redirect, SimpleCommand,
+};
+use crate::parser::lexer::{tokenize, RedirectKind, Token, Word, WordPart};
+use crate::utils::error::ShellError;
+use crate::utils::messages::{NOT_A_VALID_IDENTIFIER, UNEXPECTED_END, UNEXPECTED_TOKEN};
+use crate::variables::is_valid_name;
+use std::sync::Arc;
+
+/**
+ * The reserved words that start a compound command.
+ */
+const COMPOUND_WORDS: &[&str] = &["{", "if", "while", "until", "for", "case"];
+
+/**
+ * The reserved words that end the list of a compound command, when written as a command name.
+ */
+const CLOSING_WORDS: &[&str] = &["}", "then", "elif", "else", "fi", "do", "done", "esac"];
+
+/**
+ * The reserved words after which a command name is expected, as `then` in `if true; then ls; fi`.
+ */
+pub const OPENING_WORDS: &[&str] = &["{", "if", "then", "elif", "else", "while", "until", "do"];
+
+/**
+ * A recursive descent parser over the tokens of the lexer.
+ */
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::Newline) {
+            self.pos += 1;
+        }
+    }
+
+    /**
+     * Check whether the next token is one of the given reserved words, written without quotes.
+     */
+    fn at_reserved(&self, words: &[&str]) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if literal(word).is_some_and(|text| words.contains(&text)))
+    }
+
+    /**
+     * The error for the next token, which cannot appear there. At the end of the input,
+     * the command is incomplete rather than wrong.
+     */
+    fn unexpected_here(&self) -> ShellError {
+        match self.peek() {
+            Some(token) => unexpected(&token.to_string()),
+            None => ShellError::Incomplete(UNEXPECTED_END.to_owned()),
+        }
+    }
+
+    /**
+     * Consume the next token, which must be `expected`.
+     */
+    fn expect(&mut self, expected: &Token) -> Result<(), ShellError> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected_here());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /**
+     * Consume the reserved word `expected`.
+     */
+    fn expect_reserved(&mut self, expected: &str) -> Result<(), ShellError> {
+        if !self.at_reserved(&[expected]) {
+            return Err(self.unexpected_here());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /**
+     * list: and_or ((';' | newline) and_or)* [';']
+     *
+     * The list ends at the end of the input, at a reserved word closing a compound command,
+     * such as `}` or `fi`, or at the `;;` of a `case` item, which are left for the caller.
+     */
+    fn parse_list(&mut self) -> Result<CommandList, ShellError> {
+        let mut list = CommandList::default();
+
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                None | Some(Token::DoubleSemicolon) => break,
+                Some(Token::Word(_)) if self.at_reserved(CLOSING_WORDS) => break,
+                Some(Token::Word(_) | Token::Redirect(..) | Token::Arithmetic(_)) => list.items.push(self.parse_and_or()?),
+                Some(token) => return Err(unexpected(&token.to_string())),
+            }
+
+            match self.peek() {
+                None | Some(Token::DoubleSemicolon) => break,
+                Some(Token::Semicolon | Token::Newline) => self.pos += 1,
+                // Only a compound command can be followed by a word, as in `{ { ls; } }`.
+                Some(Token::Word(_)) if self.at_reserved(CLOSING_WORDS) => break,
+                Some(token) => return Err(unexpected(&token.to_string())),
+            }
+        }
+
+        Ok(list)
+    }
+
+    /**
+     * and_or: pipeline (('&&' | '||') linebreak pipeline)*
+     */
+    fn parse_and_or(&mut self) -> Result<AndOrList, ShellError> {
+        let mut and_or = AndOrList {
+            first: self.parse_pipeline()?,
+            rest: Vec::new(),
+        };
+
+        loop {
+            let connector = match self.peek() {
+                Some(Token::And) => Connector::And,
+                Some(Token::Or) => Connector::Or,
+                _ => break,
+            };
+            self.pos += 1;
+            self.skip_newlines();
+            and_or.rest.push((connector, self.parse_pipeline()?));
+        }
+
+        Ok(and_or)
+    }
+
+    /**
+     * pipeline: command ('|' linebreak command)*
+     */
+    fn parse_pipeline(&mut self) -> Result<Pipeline, ShellError> {
+        let mut pipeline = Pipeline::default();
+        pipeline.commands.push(self.parse_command()?);
+
+        while self.peek() == Some(&Token::Pipe) {
+            self.pos += 1;
+            self.skip_newlines();
+            pipeline.commands.push(self.parse_command()?);
+        }
+
+        Ok(pipeline)
+    }
+
+    /**
+     * command: simple_command | compound_command redirection* | function_definition
+     */
+    fn parse_command(&mut self) -> Result<Command, ShellError> {
+        let defines_function = match (self.peek(), self.tokens.get(self.pos + 1), self.tokens.get(self.pos + 2)) {
+            (Some(Token::Word(word)), Some(Token::LeftParen), Some(Token::RightParen)) => {
+                literal(word).is_some_and(|name| !name.contains('='))
+            }
+            _ => self.at_reserved(&["function"]),
+        };
+
+        if defines_function {
+            self.parse_function()
+        } else if self.at_reserved(COMPOUND_WORDS) {
+            self.parse_compound()
+        } else {
+            self.parse_simple_command().map(Command::Simple)
+        }
+    }
+
+    /**
+     * compound_command: (group | if | while | for | case) redirection*
+     */
+    fn parse_compound(&mut self) -> Result<Command, ShellError> {
+        let keyword = match self.peek() {
+            Some(Token::Word(word)) => literal(word).unwrap_or_default().to_owned(),
+            _ => String::new(),
+        };
+        let body = match keyword.as_str() {
+            "{" => self.parse_group()?,
+            "if" => self.parse_if()?,
+            "while" | "until" => self.parse_while()?,
+            "for" => self.parse_for()?,
+            "case" => self.parse_case()?,
+            _ => return Err(self.unexpected_here()),
+        };
+
+        let mut redirects = Vec::new();
+        while let Some(&Token::Redirect(fd, kind)) = self.peek() {
+            self.pos += 1;
+            redirects.push(self.parse_redirect(fd, kind)?);
+        }
+
+        Ok(Command::Compound { body, redirects })
+    }
+
+    /**
+     * A list that holds at least one command, as the parts of compound commands.
+     */
+    fn parse_compound_list(&mut self) -> Result<CommandList, ShellError> {
+        let list = self.parse_list()?;
+        if list.items.is_empty() {
+            return Err(self.unexpected_here());
+        }
+        Ok(list)
+    }
+
+    /**
+     * group: '{' compound_list '}'
+     */
+    fn parse_group(&mut self) -> Result<CompoundCommand, ShellError> {
+        self.expect_reserved("{")?;
+        let body = self.parse_compound_list()?;
+        self.expect_reserved("}")?;
+        Ok(CompoundCommand::Group(body))
+    }
+
+    /**
+     * if: 'if' compound_list 'then' compound_list ('elif' compound_list 'then' compound_list)*
+     *     ['else' compound_list] 'fi'
+     */
+    fn parse_if(&mut self) -> Result<CompoundCommand, ShellError> {
+        self.expect_reserved("if")?;
+        let mut branches = Vec::new();
+        let mut otherwise = None;
+
+        loop {
+            let condition = self.parse_compound_list()?;
+            self.expect_reserved("then")?;
+            branches.push((condition, self.parse_compound_list()?));
+
+            if self.at_reserved(&["elif"]) {
+                self.pos += 1;
+                continue;
+            }
+            if self.at_reserved(&["else"]) {
+                self.pos += 1;
+                otherwise = Some(self.parse_compound_list()?);
+            }
+            self.expect_reserved("fi")?;
+            break;
+        }
+
+        Ok(CompoundCommand::If { branches, otherwise })
+    }
+
+    /**
+     * while: ('while' | 'until') compound_list do_group
+     */
+    fn parse_while(&mut self) -> Result<CompoundCommand, ShellError> {
+        let until = self.at_reserved(&["until"]);
+        self.pos += 1;
+        let condition = self.parse_compound_list()?;
+        let body = self.parse_do_group()?;
+        Ok(CompoundCommand::While { condition, body, until })
+    }
+
+    /**
+     * for: 'for' name linebreak ['in' word* (';' | newline)] linebreak do_group
+     *    | 'for' '((' expression ';' expression ';' expression '))' [';'] linebreak do_group
+     */
+    fn parse_for(&mut self) -> Result<CompoundCommand, ShellError> {
+        self.expect_reserved("for")?;
+
+        if let Some(Token::Arithmetic(expression)) = self.peek().cloned() {
+            self.pos += 1;
+            let [init, condition, step] = split_expressions(&expression)?;
+            if self.peek() == Some(&Token::Semicolon) {
+                self.pos += 1;
+            }
+            self.skip_newlines();
+            let body = self.parse_do_group()?;
+            return Ok(CompoundCommand::ArithmeticFor { init, condition, step, body });
+        }
+
+        let name = match self.peek() {
+            Some(Token::Word(word)) => match literal(word).filter(|name| is_valid_name(name)) {
+                Some(name) => name.to_owned(),
+                None => return Err(ShellError::SyntaxError(format!("`{}': {NOT_A_VALID_IDENTIFIER}", word.text()))),
+            },
+            _ => return Err(self.unexpected_here()),
+        };
+        self.pos += 1;
+        self.skip_newlines();
+
+        let words = if self.at_reserved(&["in"]) {
+            self.pos += 1;
+            let mut words = Vec::new();
+            while let Some(Token::Word(word)) = self.peek() {
+                words.push(word.clone());
+                self.pos += 1;
+            }
+            match self.peek() {
+                Some(Token::Semicolon | Token::Newline) => self.pos += 1,
+                _ => return Err(self.unexpected_here()),
+            }
+            Some(words)
+        } else {
+            if self.peek() == Some(&Token::Semicolon) {
+                self.pos += 1;
+            }
+            None
+        };
+        self.skip_newlines();
+
+        let body = self.parse_do_group()?;
+        Ok(CompoundCommand::For { name, words, body })
+    }
+
+    /**
+     * do_group: 'do' compound_list 'done'
+     */
+    fn parse_do_group(&mut self) -> Result<CommandList, ShellError> {
+        self.expect_reserved("do")?;
+        let body = self.parse_compound_list()?;
+        self.expect_reserved("done")?;
+        Ok(body)
+    }
+
+    /**
+     * case: 'case' word linebreak 'in' linebreak (case_item (';;' linebreak case_item)*)? 'esac'
+     * case_item: ['('] word ('|' word)* ')' list
+     */
+    fn parse_case(&mut self) -> Result<CompoundCommand, ShellError> {
+        self.expect_reserved("case")?;
+        let word = match self.peek() {
+            Some(Token::Word(word)) => word.clone(),
+            _ => return Err(self.unexpected_here()),
+        };
+        self.pos += 1;
+        self.skip_newlines();
+        self.expect_reserved("in")?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_newlines();
+            if self.at_reserved(&["esac"]) {
+                self.pos += 1;
+                break;
+            }
+
+            if self.peek() == Some(&Token::LeftParen) {
+                self.pos += 1;
+            }
+            let mut patterns = vec![self.parse_pattern()?];
+            while self.peek() == Some(&Token::Pipe) {
+                self.pos += 1;
+                patterns.push(self.parse_pattern()?);
+            }
+            self.expect(&Token::RightParen)?;
+
+            let body = self.parse_list()?;
+            items.push(CaseItem { patterns, body });
+
+            if self.peek() == Some(&Token::DoubleSemicolon) {
+                self.pos += 1;
+            } else {
+                self.expect_reserved("esac")?;
+                break;
+            }
+        }
+
+        Ok(CompoundCommand::Case { word, items })
+    }
+
+    fn parse_pattern(&mut self) -> Result<Word, ShellError> {
+        match self.peek() {
+            Some(Token::Word(word)) => {
+                let word = word.clone();
+                self.pos += 1;
+                Ok(word)
+            }
+            _ => Err(self.unexpected_here()),
+        }
+    }
+
+    /**
+     * function_definition: name '(' ')' linebreak compound_command
+     *                    | 'function' name ['(' ')'] linebreak compound_command
+     */
+    fn parse_function(&mut self) -> Result<Command, ShellError> {
+        let keyword = self.tokens.get(self.pos + 1) != Some(&Token::LeftParen);
+        if keyword {
+            self.pos += 1;
+        }
+
+        let name = match self.peek() {
+            Some(Token::Word(word)) if literal(word).is_some() => word.text(),
+            _ => return Err(self.unexpected_here()),
+        };
+        self.pos += 1;
+        if !keyword || self.peek() == Some(&Token::LeftParen) {
+            self.expect(&Token::LeftParen)?;
+            self.expect(&Token::RightParen)?;
+        }
+        self.skip_newlines();
+
+        let body = self.parse_compound()?;
+        Ok(Command::Function {
+            name,
+            body: Arc::new(body),
+        })
+    }
+
+    /**
+     * command: assignment* (word | redirection)+ | '((' expression '))' redirection*
+     *
+     * Assignments are only recognized before the command name.
+     * `((expression))` is the same as `let "expression"`.
+     */
+    fn parse_simple_command(&mut self) -> Result<SimpleCommand, ShellError> {
+        let mut command = SimpleCommand::default();
+
+        let arithmetic = matches!(self.peek(), Some(Token::Arithmetic(_)));
+        if let Some(Token::Arithmetic(expression)) = self.peek().cloned() {
+            self.pos += 1;
+            let name = Word {
+                parts: vec![WordPart::Literal("let".to_owned())],
+            };
+            command.words = vec![name, expression];
+        }
+
+        loop {
+            match self.peek() {
+                Some(Token::Word(_)) if !arithmetic => {
+                    if let Some(Token::Word(word)) = self.next() {
+                        match word.assignment() {
+                            Some((name, value)) if command.words.is_empty() => {
+                                command.assignments.push(Assignment { name, value });
+                            }
+                            _ => command.words.push(word),
+                        }
+                    }
+                }
+                Some(&Token::Redirect(fd, kind)) => {
+                    self.pos += 1;
+                    command.redirects.push(self.parse_redirect(fd, kind)?);
+                }
+                _ => break,
+            }
+        }
+
+        if command.is_empty() {
+            return Err(self.unexpected_here());
+        }
+
+        Ok(command)
+    }
+
+    fn parse_redirect(&mut self, fd: Option<u32>, kind: RedirectKind) -> Result<Redirect, ShellError> {
+        let target = match self.next() {
+            Some(Token::Word(word)) => word,
+            Some(other) => return Err(unexpected(&other.to_string())),
+            None => return Err(unexpected("newline")),
+        };
+        let fd = fd.unwrap_or(match kind {
+            RedirectKind::Input => 0,
+            _ => 1,
+        });
+
+        Ok(Redirect { fd, kind, target })
+    }
+}
+
+/**
+ * Build a command list out of the tokens of one or more command lines.
+ *
+ * # Arguments
+ * * `tokens` - The tokens produced by the lexer.
+ *
+ * # Example
+ * ```rust
+ * use shell::parser::lexer::tokenize;
+ * use shell::parser::parse::parse;
+ *
+ * let list = parse(tokenize("mkdir out && cd out; ls -l | cat > out.txt").unwrap()).unwrap();
+ * assert_eq!(list.items.len(), 2);
+ * ```
+ */
+pub fn parse(tokens: Vec<Token>) -> Result<CommandList, ShellError> {
+    let mut parser = Parser { tokens, pos: 0 };
+    let list = parser.parse_list()?;
+    match parser.peek() {
+        Some(token) => Err(unexpected(&token.to_string())),
+        None => Ok(list),
+    }
+}
+
+/**
+ * Get the text of a word written without quotes or expansions, to be compared with reserved words.
+ */
+fn literal(word: &Word) -> Option<&str> {
+    match word.parts.as_slice() {
+        [WordPart::Literal(text)] => Some(text),
+        _ => None,
+    }
+}
+
+/**
+ * Split the `((init; condition; step))` of an arithmetic `for` loop on its semicolons.
+ */
+fn split_expressions(expression: &Word) -> Result<[Word; 3], ShellError> {
+    let mut words = Vec::new();
+    let mut current = Word::default();
+    for part in &expression.parts {
+        let (WordPart::Literal(text) | WordPart::Quoted(text)) = part else {
+            current.parts.push(part.clone());
+            continue;
+        };
+        for (i, piece) in text.split(';').enumerate() {
+            if i > 0 {
+                words.push(std::mem::take(&mut current));
+            }
+            if !piece.is_empty() {
+                current.parts.push(match part {
+                    WordPart::Literal(_) => WordPart::Literal(piece.to_owned()),
+                    _ => WordPart::Quoted(piece.to_owned()),
+                });
+            }
+        }
+    }
+    words.push(current);
+
+    words
+        .try_into()
+        .map_err(|_| unexpected(&Token::Arithmetic(expression.clone()).to_string()))
+}
+
+fn unexpected(token: &str) -> ShellError {
+    ShellError::SyntaxError(format!("{UNEXPECTED_TOKEN} `{}'", token))
+}
+
+/**
+ * Check whether a command line ends before its last command is complete, so that more lines
+ * should be read: an unterminated quote, a trailing `|`, `&&` or `||`, a compound command
+ * without its closing word such as `fi` or `done`, or a trailing backslash.
+ *
+ * # Example
+ * ```rust
+ * use shell::parser::parse::is_incomplete;
+ *
+ * assert!(is_incomplete("ls -l |"));
+ * assert!(!is_incomplete("ls -l | wc -l"));
+ * ```
+ */
+pub fn is_incomplete(input: &str) -> bool {
+    let backslashes = input.chars().rev().take_while(|&c| c == '\\').count();
+    backslashes % 2 == 1 || matches!(tokenize(input).and_then(parse), Err(ShellError::Incomplete(_)))
+}
+
+/**
+ * Join the lines of a command read over several lines into a single line, to be added to the history.
+ *
+ * A newline ending a command becomes a `;`, while the newlines after an operator or a reserved word
+ * such as `then` become a space. Escaped newlines are removed, and the ones inside quotes are kept.
+ *
+ * # Example
+ * ```rust
+ * use shell::parser::parse::join_lines;
+ *
+ * assert_eq!(join_lines("for x in a b\ndo\n  echo $x\ndone"), "for x in a b; do echo $x; done");
+ * ```
+ */
+pub fn join_lines(input: &str) -> String {
+    let mut lines = input.split('\n');
+    let mut joined = lines.next().unwrap_or_default().to_owned();
+
+    for line in lines {
+        let backslashes = joined.chars().rev().take_while(|&c| c == '\\').count();
+        if backslashes % 2 == 1 {
+            joined.pop();
+            joined.push_str(line);
+            continue;
+        }
+
+        match tokenize(&joined) {
+            Ok(tokens) => {
+                let line = line.trim_start();
+                if ends_command(&tokens) && !line.starts_with(';') {
+                    joined.push_str("; ");
+                } else if !tokens.is_empty() {
+                    joined.push(' ');
+                }
+                joined.push_str(line);
+            }
+            // The newline is inside quotes, and part of the command.
+            Err(_) => {
+                joined.push('\n');
+                joined.push_str(line);
+            }
+        }
+    }
+
+    joined
+}
+
+/**
+ * Check whether tokens end with a complete command, that a newline would terminate.
+ */
+fn ends_command(tokens: &[Token]) -> bool {
+    let Some(Token::Word(word)) = tokens.last() else {
+        return matches!(tokens.last(), Some(Token::Arithmetic(_)));
+    };
+
+    let last = tokens.len() - 1;
+    match literal(word) {
+        // `in` is only a reserved word in `for NAME in` and `case WORD in`.
+        Some("in") => !(last >= 2 && is_reserved(tokens, last - 2, &["for", "case"])),
+        Some(text) => !(OPENING_WORDS.contains(&text) && in_command_position(tokens, last)),
+        None => true,
+    }
+}
+
+/**
+ * Check whether the token at the given index is one of the given reserved words, in command position.
+ */
+fn is_reserved(tokens: &[Token], index: usize, words: &[&str]) -> bool {
+    let Token::Word(word) = &tokens[index] else {
+        return false;
+    };
+    literal(word).is_some_and(|text| words.contains(&text)) && in_command_position(tokens, index)
+}
+
+/**
+ * Check whether the token at the given index starts a command: it comes first, or after an operator
+ * or a reserved word such as `then`.
+ */
+fn in_command_position(tokens: &[Token], index: usize) -> bool {
+    match index.checked_sub(1).map(|i| &tokens[i]) {
+        None => true,
+        Some(Token::Word(previous)) => literal(previous).is_some_and(|text| OPENING_WORDS.contains(&text)),
+        Some(Token::Redirect(..) | Token::Arithmetic(_)) => false,
+        Some(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::{CompoundCommand, Connector};
+    use crate::parser::lexer::tokenize;
+
+    fn parse_line(input: &str) -> Result<CommandList, ShellError> {
+        tokenize(input).and_then(parse)
+    }
+
+    fn words(command: &Command) -> Vec<String> {
+        match command {
+            Command::Simple(command) => command.words.iter().map(Word::text).collect(),
+            _ => panic!("expected a simple command"),
+        }
+    }
+
+    #[test]
+    fn parses_lists_and_pipelines() {
+        let list = parse_line("mkdir out && cd out; ls -l | cat > out.txt").unwrap();
+        assert_eq!(list.items.len(), 2);
+
+        let first = &list.items[0];
+        assert_eq!(words(&first.first.commands[0]), ["mkdir", "out"]);
+        assert_eq!(first.rest[0].0, Connector::And);
+        assert_eq!(words(&first.rest[0].1.commands[0]), ["cd", "out"]);
+
+        let pipeline = &list.items[1].first;
+        assert_eq!(pipeline.commands.len(), 2);
+        let Command::Simple(cat) = &pipeline.commands[1] else {
+            panic!("expected a simple command");
+        };
+        assert_eq!(cat.redirects.len(), 1);
+    }
+
+    #[test]
+    fn parses_compound_commands() {
+        let list = parse_line("if true; then echo yes; else echo no; fi").unwrap();
+        let Command::Compound { body: CompoundCommand::If { branches, otherwise }, .. } = &list.items[0].first.commands[0]
+        else {
+            panic!("expected an if command");
+        };
+        assert_eq!(branches.len(), 1);
+        assert!(otherwise.is_some());
+
+        let list = parse_line("for x in a b; do echo $x; done").unwrap();
+        let Command::Compound { body: CompoundCommand::For { name, words: Some(words), .. }, .. } =
+            &list.items[0].first.commands[0]
+        else {
+            panic!("expected a for loop");
+        };
+        assert_eq!(name, "x");
+        assert_eq!(words.len(), 2);
+
+        let list = parse_line("case $1 in a|b) echo ab;; *) echo other;; esac").unwrap();
+        let Command::Compound { body: CompoundCommand::Case { items, .. }, .. } = &list.items[0].first.commands[0] else {
+            panic!("expected a case command");
+        };
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].patterns.len(), 2);
+    }
+
+    #[test]
+    fn parses_function_definitions() {
+        let list = parse_line("greet() { echo hi; }").unwrap();
+        assert!(matches!(&list.items[0].first.commands[0], Command::Function { name, .. } if name == "greet"));
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert!(matches!(parse_line("| ls"), Err(ShellError::SyntaxError(_))));
+        assert!(matches!(parse_line("if true; then echo"), Err(ShellError::Incomplete(_))));
+    }
+
+    #[test]
+    fn detects_incomplete_input() {
+        assert!(is_incomplete("ls -l |"));
+        assert!(is_incomplete("while true; do"));
+        assert!(is_incomplete("echo 'abc"));
+        assert!(!is_incomplete("ls -l | wc -l"));
+    }
+
+    #[test]
+    fn joins_lines_for_the_history() {
+        assert_eq!(join_lines("for x in a b\ndo\n  echo $x\ndone"), "for x in a b; do echo $x; done");
+        assert_eq!(join_lines("if true; then\necho in\nfi"), "if true; then echo in; fi");
+        assert_eq!(join_lines("case y in\ny) echo y;;\nesac"), "case y in y) echo y;; esac");
+        assert_eq!(join_lines("echo \"a\nb\""), "echo \"a\nb\"");
+        assert_eq!(join_lines("echo a \\\nb"), "echo a b");
+    }
+}
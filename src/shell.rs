@@ -1,30 +1,130 @@
 use crate::commands::{
+    alias::{alias, unalias},
     cat::cat,
     cp::cp,
     echo::echo,
-    exit::clear,
+    env::{env, printenv},
+    exit::{clear, exit},
+    export::export,
+    history::history,
+    r#let::r#let,
+    local::local,
+    loop_control::{r#break, r#continue},
     ls::ls,
     mkdir::mkdir,
     mv::mv,
     pwd::pwd,
+    r#return::r#return,
     rm::rm,
+    set::set,
+    source::source,
     touch::touch,
+    unset::unset,
 };
+use crate::editor::history::History;
+use crate::exec::external::run_external;
+use crate::exec::function::call_function;
+use crate::exec::list::run_list;
+use crate::exec::stream::Streams;
+use crate::parser::alias::expand_aliases;
+use crate::parser::ast::Command;
+use crate::parser::lexer::tokenize;
+use crate::parser::parse::parse;
 use crate::utils::error::ShellError;
-use crate::utils::messages::{CANNOT_ACCESS, DIR_NOT_FOUND, MANY_ARGS, NOT_A_DIRECTORY, NO_SUCH_FILE};
-use crate::utils::path::get_home_dir;
+use crate::utils::messages::{CANNOT_ACCESS, DIR_NOT_FOUND, MANY_ARGS, NOT_A_DIRECTORY, PERMISSION_DENIED};
+use crate::utils::path::{get_home_dir, is_searchable};
+use crate::variables::{Variable, Variables};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs::metadata;
-use std::io::Error;
-use std::path::PathBuf;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/**
+ * The name of the shell, used as `$0` when it does not run a script.
+ */
+pub const SHELL_NAME: &str = "0-shell";
+
+/**
+ * The names of the builtins handled by `Shell::run_command`, in the order they are dispatched
+ * after the functions.
+ */
+pub const BUILTINS: &[&str] = &[
+    "cd", "pwd", "ls", "echo", "mkdir", "touch", "cat", "cp", "mv", "rm", "set", "exit", "export", "unset", "env",
+    "printenv", "let", "history", "alias", "unalias", "local", "return", "break", "continue", "source", ".", "command", "clear",
+];
+
+/**
+ * The options of the shell, changed with the `set` builtin.
+ */
+#[derive(Clone, Default)]
+pub struct ShellOptions {
+    pub pipefail: bool,
+    /// Patterns that match no file expand to nothing.
+    pub nullglob: bool,
+    /// Patterns that match no file are an error.
+    pub failglob: bool,
+}
+
+impl ShellOptions {
+    /**
+     * Get the names and values of every option.
+     */
+    pub fn list(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("failglob", self.failglob),
+            ("nullglob", self.nullglob),
+            ("pipefail", self.pipefail),
+        ]
+    }
+
+    /**
+     * Get a mutable reference to an option from its name.
+     */
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "pipefail" => Some(&mut self.pipefail),
+            "nullglob" => Some(&mut self.nullglob),
+            "failglob" => Some(&mut self.failglob),
+            _ => None,
+        }
+    }
+}
 
 /**
  * The shell struct.
  *
- * The shell keeps track of the current directory and executes commands.
+ * The shell keeps track of the current directory, the variables, the history and the status
+ * of the last command, and executes commands.
  */
+#[derive(Clone)]
 pub struct Shell {
     current_dir: PathBuf,
+    /// The name of the shell, or of the script it runs, as `$0`.
+    pub name: String,
+    /// Whether the shell reads its commands from a terminal, rather than from a script or `-c`.
+    pub interactive: bool,
+    pub options: ShellOptions,
+    pub variables: Variables,
+    /// The aliases, sorted by name to be listed by `alias`.
+    pub aliases: BTreeMap<String, String>,
+    /// The functions defined with `name() { ...; }`, by name.
+    pub functions: HashMap<String, Arc<Command>>,
+    /// The positional parameters `$1`, `$2`, ... of the function being run.
+    pub positional: Vec<String>,
+    /// The variables declared with `local` in each function being run, with their state before it.
+    pub locals: Vec<Vec<(String, Option<Variable>)>>,
+    /// The number of loops being run, that `break` and `continue` can leave.
+    pub loops: usize,
+    /// The number of files being run by `source`, that `return` can leave.
+    pub sourcing: usize,
+    pub history: History,
+    pub last_status: i32,
+    /// The file and line of the command being run from a file, shown before its errors.
+    pub location: Option<String>,
+    /// Whether the shell exits once the error being returned is reported, as after `${name?message}`.
+    pub exit_on_error: bool,
 }
 
 impl Shell {
@@ -33,52 +133,161 @@ impl Shell {
      */
     pub fn new() -> Self {
         Shell {
-            current_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from("/"))
+            current_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+            name: SHELL_NAME.to_owned(),
+            interactive: false,
+            options: ShellOptions::default(),
+            variables: Variables::from_env(),
+            aliases: BTreeMap::new(),
+            functions: HashMap::new(),
+            positional: Vec::new(),
+            locals: Vec::new(),
+            loops: 0,
+            sourcing: 0,
+            history: History::default(),
+            last_status: 0,
+            location: None,
+            exit_on_error: false,
+        }
+    }
+
+    /**
+     * Get the current directory of the shell.
+     */
+    pub fn current_dir(&self) -> &Path {
+        &self.current_dir
+    }
+
+    /**
+     * Get the value of a parameter: a variable, a positional parameter such as `1`, or a special
+     * parameter such as `?` for the status of the last command or `$` for the process id of the shell.
+     *
+     * `@` and `*` join the positional parameters, with a space or the first character of `IFS`.
+     */
+    pub fn param(&self, name: &str) -> Option<String> {
+        match name {
+            "?" => Some(self.last_status.to_string()),
+            "$" => Some(std::process::id().to_string()),
+            "0" => Some(self.name.clone()),
+            "#" => Some(self.positional.len().to_string()),
+            "@" => Some(self.positional.join(" ")),
+            "*" => {
+                let separator = self.variables.get("IFS").map_or(" ", |ifs| ifs.get(..1).unwrap_or_default());
+                Some(self.positional.join(separator))
+            }
+            _ if name.bytes().all(|b| b.is_ascii_digit()) => {
+                let index = name.parse::<usize>().ok()?;
+                self.positional.get(index.checked_sub(1)?).cloned()
+            }
+            _ => self.variables.get(name).map(str::to_owned),
+        }
+    }
+
+    /**
+     * Execute a command line in the shell.
+     *
+     * The line is split into tokens by the lexer, the aliases used as command names are expanded,
+     * and the tokens are parsed into a command list, which is then run with the terminal as its streams.
+     *
+     * Returns the exit status of the last pipeline that ran. Syntax errors are returned
+     * as errors, after setting the status to 2.
+     */
+    pub fn execute(&mut self, input: &str) -> Result<i32, ShellError> {
+        self.execute_with(input, &Streams::inherit())
+    }
+
+    /**
+     * Execute a command line in the shell with the given streams, as for the commands of a file
+     * run by `source` with redirections.
+     */
+    pub fn execute_with(&mut self, input: &str, streams: &Streams) -> Result<i32, ShellError> {
+        let list = tokenize(input)
+            .and_then(|tokens| expand_aliases(tokens, &self.aliases))
+            .and_then(parse)
+            .inspect_err(|e| self.last_status = e.status())?;
+        run_list(self, &list, streams)
+    }
+
+    /**
+     * Run a single command with the given streams.
+     *
+     * The first word is used as the command name. Functions are looked up first, then builtins,
+     * and other names are looked up in `PATH` and run as external programs.
+     *
+     * Returns the exit status of the command.
+     */
+    pub fn run_command(&mut self, words: &[String], streams: &mut Streams) -> Result<i32, ShellError> {
+        if let Some(body) = words.first().and_then(|name| self.functions.get(name)).cloned() {
+            return call_function(self, &words[0], &body, &words[1..], streams);
         }
+        self.run_builtin(words, streams)
     }
 
     /**
-     * Execute a command in the shell.
+     * Run a builtin or an external program, without looking at functions.
      *
-     * The command is split into parts by whitespace, and the first part is used as the command name.
+     * `command name args...` calls this directly, so that a function can wrap the command it is named after.
      */
-    pub fn execute(&mut self, input: &str) -> Result<(), ShellError> {
-        let parts: Vec<&str> = input.split_whitespace().collect();
+    fn run_builtin(&mut self, words: &[String], streams: &mut Streams) -> Result<i32, ShellError> {
+        let parts: Vec<&str> = words.iter().map(String::as_str).collect();
         if parts.is_empty() {
-            return Ok(());
+            return Ok(0);
         }
 
         match parts[0] {
             "cd" => self.cd(&parts[1..]),
-            "pwd" => pwd(&self.current_dir),
-            "ls" => ls(&self.current_dir, &parts[1..]),
-            "echo" => echo(&parts[1..]),
+            "pwd" => pwd(&self.current_dir, &mut streams.stdout),
+            "ls" => ls(&self.current_dir, &parts[1..], &mut streams.stdout),
+            "echo" => echo(&parts[1..], &mut streams.stdout),
             "mkdir" => mkdir(&self.current_dir, &parts[1..]),
             "touch" => touch(&self.current_dir, &parts[1..]),
-            "cat" => cat(&self.current_dir, &parts[1..]),
+            "cat" => cat(&self.current_dir, &parts[1..], &mut streams.stdin, &mut streams.stdout),
             "cp" => cp(&self.current_dir, &parts[1..]),
             "mv" => mv(&self.current_dir, &parts[1..]),
             "rm" => rm(&self.current_dir, &parts[1..]),
-            "clear" => Ok(clear()),
-            _ => Err(ShellError::CommandNotFound(parts[0].to_owned())),
+            "set" => set(&mut self.options, &parts[1..], &mut streams.stdout),
+            "exit" => exit(self.last_status, &parts[1..], &mut streams.stderr),
+            "export" => export(&mut self.variables, &parts[1..], &mut streams.stdout),
+            "unset" => unset(&mut self.variables, &parts[1..]),
+            "env" => return env(&self.current_dir, &self.variables.exported(), &parts[1..], streams),
+            "printenv" => return printenv(&self.variables.exported(), &parts[1..], &mut streams.stdout),
+            "let" => return r#let(&mut self.variables, &parts[1..]),
+            "history" => history(&mut self.history, &parts[1..], &mut streams.stdout),
+            "alias" => alias(&mut self.aliases, &parts[1..], &mut streams.stdout),
+            "unalias" => unalias(&mut self.aliases, &parts[1..]),
+            "local" => local(&mut self.variables, self.locals.last_mut(), &parts[1..]),
+            "return" => {
+                let can_return = !self.locals.is_empty() || self.sourcing > 0;
+                r#return(self.last_status, can_return, &parts[1..], &mut streams.stderr)
+            }
+            "break" => r#break(self.loops, &parts[1..], &mut streams.stderr),
+            "continue" => r#continue(self.loops, &parts[1..], &mut streams.stderr),
+            "source" | "." => return source(self, parts[0], &parts[1..], streams),
+            "command" => return self.run_builtin(&words[1..], streams),
+            "clear" => clear(&mut streams.stdout).map_err(ShellError::from),
+            _ => return run_external(&self.current_dir, parts[0], &parts[1..], &self.variables.exported(), streams),
         }?;
 
-        Ok(())
+        Ok(0)
     }
 
     /**
      * Change the current directory of the shell.
      *
      * If no arguments are provided, the shell will change to the home directory.
+     * `PWD` and `OLDPWD` are updated with the new and previous directories.
+     *
+     * Only the directory of this shell changes, not the one of the process, so that `cd` in a stage
+     * of a pipeline or in a command substitution does not affect the shell running them.
      */
     pub fn cd(&mut self, args: &[&str]) -> Result<(), ShellError> {
         if args.len() > 1 {
             return Err(ShellError::InvalidArguments(format!("cd: {}", MANY_ARGS)));
         }
 
-        let new_dir = match args.get(0) {
+        let new_dir = match args.first() {
             Some(&"") | None => {
-                get_home_dir()?
+                get_home_dir(&self.variables)?
             }
             Some(&path) => {
                 if path.starts_with('/') {
@@ -104,25 +313,30 @@ impl Shell {
             }
             
             if metadata.is_dir() {
-                env::set_current_dir(&new_dir).map_err(|e| {
-                    ShellError::IoError(Error::new(
-                        e.kind(),
-                        format!("cd: {CANNOT_ACCESS} '{}': {NO_SUCH_FILE}", new_dir.display()),
-                    ))
-                })?;
+                // The process keeps its directory: copies of the shell, running command substitutions
+                // and pipelines in other threads, must not change the directory of the others.
+                if !is_searchable(&new_dir) {
+                    return Err(ShellError::IoError(Error::new(
+                        ErrorKind::PermissionDenied,
+                        format!("cd: {CANNOT_ACCESS} '{}': {PERMISSION_DENIED}", new_dir.display()),
+                    )));
+                }
 
+                let old_dir = self.current_dir.to_string_lossy().into_owned();
                 if let Some(step_back) = back {
                     self.current_dir = PathBuf::from(step_back);
                 } else {
                     self.current_dir = new_dir;
                 }
+                self.variables.set("OLDPWD", &old_dir);
+                self.variables.set("PWD", &self.current_dir.to_string_lossy());
 
                 Ok(())
             } else {
-                Err(ShellError::InvalidArguments(format!("cd: {}", NOT_A_DIRECTORY)))
+                Err(ShellError::IoError(Error::new(ErrorKind::NotADirectory, format!("cd: {}", NOT_A_DIRECTORY))))
             }
         } else {
-            Err(ShellError::InvalidArguments(format!("cd: {}", DIR_NOT_FOUND)))
+            Err(ShellError::IoError(Error::new(ErrorKind::NotFound, format!("cd: {}", DIR_NOT_FOUND))))
         }
     }
 }
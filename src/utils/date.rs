@@ -1,6 +1,32 @@
 use crate::utils::messages::{NOTHING, TIME_WENT_BACKWARDS};
+#[cfg(target_os = "linux")]
+use std::os::raw::{c_char, c_int, c_long};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/**
+ * The broken-down time of `time.h`, as laid out on Linux.
+ */
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct CTm {
+    tm_sec: c_int,
+    tm_min: c_int,
+    tm_hour: c_int,
+    tm_mday: c_int,
+    tm_mon: c_int,
+    tm_year: c_int,
+    tm_wday: c_int,
+    tm_yday: c_int,
+    tm_isdst: c_int,
+    tm_gmtoff: c_long,
+    tm_zone: *const c_char,
+}
+
+#[cfg(target_os = "linux")]
+extern "C" {
+    fn localtime_r(time: *const i64, result: *mut CTm) -> *mut CTm;
+}
+
 /**
  * Format a datetime to a string.
  *
@@ -85,13 +111,65 @@ fn secs_to_tm(secs: u64) -> Tm {
     let secs_in_day = secs % SECS_PER_DAY;
     let hour = (secs_in_day / 3600) % 24;
     let min = (secs_in_day % 3600) / 60;
+    // The 1st of January 1970 was a Thursday.
+    let wday = (secs / SECS_PER_DAY + 4) % 7;
 
     Tm {
         tm_mon: month as i32,
         tm_mday: day as i32,
         tm_hour: hour as i32,
         tm_min: min as i32,
+        tm_sec: (secs_in_day % 60) as i32,
+        tm_wday: wday as i32,
+    }
+}
+
+/**
+ * Convert a time to a `Tm` struct in the local time zone, or in UTC where it cannot be known.
+ *
+ * # Arguments
+ * * `time` - The time to convert.
+ *
+ * # Example
+ * ```rust
+ * use std::time::SystemTime;
+ * use shell::utils::date::local_tm;
+ *
+ * let now = local_tm(SystemTime::now());
+ * println!("{:02}:{:02}", now.tm_hour, now.tm_min);
+ * ```
+ */
+pub fn local_tm(time: SystemTime) -> Tm {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    localtime(secs).unwrap_or_else(|| secs_to_tm(secs))
+}
+
+/**
+ * Convert seconds to a `Tm` struct in the local time zone with `localtime_r`.
+ */
+#[cfg(target_os = "linux")]
+fn localtime(secs: u64) -> Option<Tm> {
+    let mut tm = unsafe { std::mem::zeroed::<CTm>() };
+    if unsafe { localtime_r(&(secs as i64), &mut tm) }.is_null() {
+        return None;
     }
+
+    Some(Tm {
+        tm_mon: tm.tm_mon,
+        tm_mday: tm.tm_mday,
+        tm_hour: tm.tm_hour,
+        tm_min: tm.tm_min,
+        tm_sec: tm.tm_sec,
+        tm_wday: tm.tm_wday,
+    })
+}
+
+/**
+ * Elsewhere the layout of `struct tm` is not known, so the time is left in UTC.
+ */
+#[cfg(not(target_os = "linux"))]
+fn localtime(_secs: u64) -> Option<Tm> {
+    None
 }
 
 /**
@@ -102,6 +180,9 @@ pub struct Tm {
     pub tm_mday: i32,
     pub tm_hour: i32,
     pub tm_min: i32,
+    pub tm_sec: i32,
+    /// The day of the week, from 0 for Sunday.
+    pub tm_wday: i32,
 }
 
 const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
@@ -120,7 +201,7 @@ const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31
  * ```
  */
 fn is_leap_year(year: u64) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
 }
 
 /**
@@ -137,7 +218,7 @@ fn is_leap_year(year: u64) -> bool {
  * let month_str = month_to_str(month);
  * ```
  */
-fn month_to_str(month: i32) -> &'static str {
+pub fn month_to_str(month: i32) -> &'static str {
     match month {
         0 => "Jan",
         1 => "Feb",
@@ -153,4 +234,30 @@ fn month_to_str(month: i32) -> &'static str {
         11 => "Dec",
         _ => NOTHING,
     }
+}
+
+/**
+ * Convert a day of the week, from 0 for Sunday, to a string.
+ *
+ * # Arguments
+ * * `day` - The day to convert.
+ *
+ * # Example
+ * ```rust
+ * use shell::utils::date::weekday_to_str;
+ *
+ * assert_eq!(weekday_to_str(0), "Sun");
+ * ```
+ */
+pub fn weekday_to_str(day: i32) -> &'static str {
+    match day {
+        0 => "Sun",
+        1 => "Mon",
+        2 => "Tue",
+        3 => "Wed",
+        4 => "Thu",
+        5 => "Fri",
+        6 => "Sat",
+        _ => NOTHING,
+    }
 }
\ No newline at end of file
@@ -10,6 +10,40 @@ pub enum ShellError {
     IoError(Error),
     CommandNotFound(String),
     InvalidArguments(String),
+    SyntaxError(String),
+    /// A syntax error at the end of the input, which more lines could complete.
+    Incomplete(String),
+    NotExecutable(String),
+    ExpansionError(String),
+    Exit(i32),
+    /// `return` leaving the function being run, with its status.
+    Return(i32),
+    /// `break` leaving the given number of enclosing loops.
+    Break(u32),
+    /// `continue` going on with the next iteration of the given enclosing loop.
+    Continue(u32),
+    /// Ctrl+C stopping the running command list, up to the prompt.
+    Interrupted,
+}
+
+impl ShellError {
+    /**
+     * Returns the exit status matching the error
+     *
+     * 1 for general failures, 2 for usage and syntax errors,
+     * 126 for programs that cannot be executed and 127 for unknown commands.
+     */
+    pub fn status(&self) -> i32 {
+        match self {
+            ShellError::IoError(_) | ShellError::ExpansionError(_) => 1,
+            ShellError::InvalidArguments(_) | ShellError::SyntaxError(_) | ShellError::Incomplete(_) => 2,
+            ShellError::NotExecutable(_) => 126,
+            ShellError::CommandNotFound(_) => 127,
+            ShellError::Exit(code) | ShellError::Return(code) => *code,
+            ShellError::Break(_) | ShellError::Continue(_) => 0,
+            ShellError::Interrupted => 130,
+        }
+    }
 }
 
 impl error::Error for ShellError {
@@ -42,6 +76,16 @@ impl Display for ShellError {
             ShellError::IoError(e) => write!(f, "\x1b[31m{}\x1b[0m", e),
             ShellError::CommandNotFound(cmd) => write!(f, "\x1b[31mCommand '{}' not found\x1b[0m", cmd),
             ShellError::InvalidArguments(msg) => write!(f, "\x1b[31m{}\x1b[0m", msg),
+            ShellError::SyntaxError(msg) | ShellError::Incomplete(msg) => {
+                write!(f, "\x1b[31msyntax error: {}\x1b[0m", msg)
+            }
+            ShellError::NotExecutable(msg) => write!(f, "\x1b[31m{}\x1b[0m", msg),
+            ShellError::ExpansionError(msg) => write!(f, "\x1b[31m{}\x1b[0m", msg),
+            ShellError::Exit(_)
+            | ShellError::Return(_)
+            | ShellError::Break(_)
+            | ShellError::Continue(_)
+            | ShellError::Interrupted => Ok(()),
         }
     }
 }
\ No newline at end of file
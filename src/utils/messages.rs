@@ -21,4 +21,38 @@ pub const NOT_SPECIFIED: &str = "no file or directory specified";
 pub const NO_HOME_DIRECTORY: &str = "no home directory found";
 pub const CANNOT_READ_INPUT: &str = "Error reading input";
 pub const IS_A_DIRECTORY: &str = "is a directory";
-pub const COMMAND_NOT_FOUND: &str = "command not found";
\ No newline at end of file
+pub const PERMISSION_DENIED: &str = "permission denied";
+pub const UNEXPECTED_EOF: &str = "unexpected end of file while looking for matching";
+pub const UNEXPECTED_TOKEN: &str = "near unexpected token";
+pub const INVALID_OPTION_NAME: &str = "invalid option name";
+pub const NUMERIC_ARGUMENT_REQUIRED: &str = "numeric argument required";
+pub const BAD_FILE_DESCRIPTOR: &str = "bad file descriptor";
+pub const BAD_SUBSTITUTION: &str = "bad substitution";
+pub const CANNOT_ASSIGN: &str = "cannot assign in this way";
+pub const PARAMETER_NOT_SET: &str = "parameter null or not set";
+pub const NOT_A_VALID_IDENTIFIER: &str = "not a valid identifier";
+pub const DIVISION_BY_ZERO: &str = "division by 0";
+pub const ARITHMETIC_SYNTAX_ERROR: &str = "syntax error in expression";
+pub const ARITHMETIC_OVERFLOW: &str = "integer overflow";
+pub const NEGATIVE_EXPONENT: &str = "exponent less than 0";
+pub const INVALID_NUMBER: &str = "invalid number";
+pub const RECURSION_LEVEL_EXCEEDED: &str = "expression recursion level exceeded";
+pub const EXPRESSION_EXPECTED: &str = "expression expected";
+pub const NO_MATCH: &str = "no match";
+pub const POSITION_OUT_OF_RANGE: &str = "history position out of range";
+pub const EVENT_NOT_FOUND: &str = "event not found";
+pub const BAD_WORD_SPECIFIER: &str = "bad word specifier";
+pub const SUBSTITUTION_FAILED: &str = "substitution failed";
+pub const INVALID_OPTION: &str = "invalid option";
+pub const UNEXPECTED_END: &str = "unexpected end of file";
+pub const NOT_FOUND: &str = "not found";
+pub const INVALID_ALIAS_NAME: &str = "invalid alias name";
+pub const NOT_IN_FUNCTION: &str = "can only be used in a function";
+pub const RETURN_OUTSIDE_FUNCTION: &str = "can only `return' from a function or sourced script";
+pub const ONLY_IN_LOOP: &str = "only meaningful in a `for', `while', or `until' loop";
+pub const LOOP_COUNT_OUT_OF_RANGE: &str = "loop count out of range";
+pub const OPTION_REQUIRES_ARGUMENT: &str = "option requires an argument";
+pub const FILENAME_REQUIRED: &str = "filename argument required";
+pub const FILE_NOT_FOUND: &str = "file not found";
+pub const MAX_NESTING_EXCEEDED: &str = "maximum function nesting level exceeded";
+pub const MAX_SOURCE_NESTING_EXCEEDED: &str = "maximum source nesting level exceeded";
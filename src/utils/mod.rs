@@ -1,6 +1,8 @@
 pub(crate) mod error;
 pub(crate) mod path;
 pub(crate) mod date;
+#[allow(clippy::module_inception)]
 pub(crate) mod utils;
 pub(crate) mod color;
-pub(crate) mod messages;
\ No newline at end of file
+pub(crate) mod messages;
+pub(crate) mod signal;
\ No newline at end of file
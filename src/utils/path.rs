@@ -1,23 +1,62 @@
 use crate::utils::error::ShellError;
 use crate::utils::messages::NO_HOME_DIRECTORY;
-use std::env;
-use std::io::{Error, ErrorKind};
-use std::path::PathBuf;
+use crate::variables::Variables;
+use std::fs::File;
+use std::ffi::CString;
+use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::os::raw::{c_char, c_int};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 
 /**
- * Get the home directory of the current user.
+ * The mode of `access` checking the execute permission, or the search permission of a directory.
+ */
+const X_OK: c_int = 1;
+
+extern "C" {
+    fn access(path: *const c_char, mode: c_int) -> c_int;
+}
+
+/**
+ * Get the home directory of the current user, from the variables of the shell.
 */
-pub fn get_home_dir() -> Result<PathBuf, ShellError> {
+pub fn get_home_dir(variables: &Variables) -> Result<PathBuf, ShellError> {
     #[cfg(unix)]
     let home_var = "HOME";
     #[cfg(windows)]
     let home_var = "USERPROFILE";
 
 
-    env::var_os(home_var)
+    variables.get(home_var)
         .map(PathBuf::from)
         .ok_or_else(|| ShellError::IoError(Error::new(
             ErrorKind::NotFound,
             NO_HOME_DIRECTORY,
         )))
+}
+
+/**
+ * Get the home directory of a user by reading /etc/passwd.
+ */
+pub fn get_user_home_dir(user: &str) -> Option<PathBuf> {
+    let file = File::open("/etc/passwd").ok()?;
+
+    let reader = BufReader::new(file);
+    for line in reader.lines().map_while(Result::ok) {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() >= 6 && parts[0] == user {
+            return Some(PathBuf::from(parts[5]));
+        }
+    }
+    None
+}
+
+/**
+ * Check whether the current user can search a directory, which is needed to make it the current directory.
+ */
+pub fn is_searchable(path: &Path) -> bool {
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { access(path.as_ptr(), X_OK) == 0 }
 }
\ No newline at end of file
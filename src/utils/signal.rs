@@ -0,0 +1,50 @@
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const SIGINT: c_int = 2;
+const SIGQUIT: c_int = 3;
+
+extern "C" {
+    fn signal(signum: c_int, handler: usize) -> usize;
+}
+
+/**
+ * Set when the user presses Ctrl+C, until the shell is back at the prompt.
+ */
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_signal(signum: c_int) {
+    if signum == SIGINT {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+}
+
+/**
+ * Keep the shell alive when the user presses Ctrl+C or Ctrl+\ while a child runs.
+ *
+ * A handler is installed instead of ignoring the signals, because handlers are reset
+ * on `exec` while ignored signals would be inherited by every spawned program.
+ */
+pub fn ignore_interrupts() {
+    unsafe {
+        signal(SIGINT, on_signal as *const () as usize);
+        signal(SIGQUIT, on_signal as *const () as usize);
+    }
+}
+
+/**
+ * Whether Ctrl+C was pressed since the last call to `clear_interrupt`.
+ *
+ * Loops and command lists stop when it is set, so that Ctrl+C leaves `while true; do sleep 1; done`
+ * and goes back to the prompt.
+ */
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/**
+ * Forget a Ctrl+C, before running a new command from the prompt.
+ */
+pub fn clear_interrupt() {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+}
@@ -2,7 +2,7 @@ use std::fs::{File, Permissions};
 use std::io::{BufRead, BufReader};
 use std::os::unix::fs::PermissionsExt;
 
-/// Convert a UID to a username by reading /etc/passwd
+// Convert a UID to a username by reading /etc/passwd
 /*pub fn uid_to_name(uid: u32) -> String {
     let file = match File::open("/etc/passwd") {
         Ok(file) => file,
@@ -31,21 +31,19 @@ pub fn extrac_guid_from_file(uid: u32, file: &str) -> String {
     };
 
     let reader = BufReader::new(file);
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 3 {
-                let file_uid = parts[2].parse::<u32>().ok();
-                if file_uid == Option::from(uid) {
-                    return parts[0].to_string();
-                }
+    for line in reader.lines().map_while(Result::ok) {
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() >= 3 {
+            let file_uid = parts[2].parse::<u32>().ok();
+            if file_uid == Option::from(uid) {
+                return parts[0].to_string();
             }
         }
     }
     "ERROR".to_string()
 }
 
-/// Convert a GID to a group name by reading /etc/group
+// Convert a GID to a group name by reading /etc/group
 /*pub fn gid_to_name(gid: u32) -> String {
     let file = match File::open("/etc/group") {
         Ok(file) => file,
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::env;
+
+/**
+ * A shell variable and whether it is passed to the environment of spawned programs.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    pub value: String,
+    pub exported: bool,
+}
+
+/**
+ * The variables of the shell.
+ *
+ * The store starts with the environment of the shell process, which is exported.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    vars: HashMap<String, Variable>,
+}
+
+impl Variables {
+    /**
+     * Create a variable store from the environment of the current process.
+     */
+    pub fn from_env() -> Self {
+        let vars = env::vars()
+            .map(|(name, value)| (name, Variable { value, exported: true }))
+            .collect();
+
+        Variables { vars }
+    }
+
+    /**
+     * Get the value of a variable.
+     */
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(|var| var.value.as_str())
+    }
+
+    /**
+     * Get a copy of a variable, with its exported flag.
+     */
+    pub fn get_variable(&self, name: &str) -> Option<Variable> {
+        self.vars.get(name).cloned()
+    }
+
+    /**
+     * Set the value of a variable, keeping its exported flag if it already exists.
+     */
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self.vars.get_mut(name) {
+            Some(var) => var.value = value.to_owned(),
+            None => {
+                self.vars.insert(name.to_owned(), Variable { value: value.to_owned(), exported: false });
+            }
+        }
+    }
+
+    /**
+     * Mark a variable as exported, optionally setting its value.
+     *
+     * Exporting a variable that does not exist yet creates it with an empty value.
+     */
+    pub fn export(&mut self, name: &str, value: Option<&str>) {
+        let var = self.vars.entry(name.to_owned()).or_insert_with(|| Variable {
+            value: String::new(),
+            exported: true,
+        });
+        var.exported = true;
+        if let Some(value) = value {
+            var.value = value.to_owned();
+        }
+    }
+
+    /**
+     * Remove a variable.
+     */
+    pub fn unset(&mut self, name: &str) -> Option<Variable> {
+        self.vars.remove(name)
+    }
+
+    /**
+     * Put back a variable saved with `get_variable`, or remove it if it did not exist.
+     */
+    pub fn restore(&mut self, name: &str, var: Option<Variable>) {
+        match var {
+            Some(var) => {
+                self.vars.insert(name.to_owned(), var);
+            }
+            None => {
+                self.vars.remove(name);
+            }
+        }
+    }
+
+    /**
+     * Get the names of every variable, in no particular order.
+     */
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.vars.keys().map(String::as_str)
+    }
+
+    /**
+     * Get the exported variables, sorted by name, as passed to spawned programs.
+     */
+    pub fn exported(&self) -> Vec<(String, String)> {
+        let mut exported: Vec<(String, String)> = self
+            .vars
+            .iter()
+            .filter(|(_, var)| var.exported)
+            .map(|(name, var)| (name.clone(), var.value.clone()))
+            .collect();
+        exported.sort();
+        exported
+    }
+}
+
+/**
+ * Check whether a string is a valid variable name: a letter or `_` followed by letters, digits or `_`.
+ */
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        _ => false,
+    }
+}